@@ -0,0 +1,76 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::path::Path;
+use std::process::Command;
+
+// Provides to the build the versions displayed by `ros2-teleop --version`:
+//  - ZENOH_VERSION and CDR_VERSION: the versions of the zenoh and cdr crates, as resolved in Cargo.lock
+//  - GIT_COMMIT: the git commit of the sources (with a "-dirty" suffix if modified)
+fn main() {
+    let lock_file = Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock_file.display());
+    let lock = std::fs::read_to_string(&lock_file).unwrap_or_default();
+    println!(
+        "cargo:rustc-env=ZENOH_VERSION={}",
+        locked_version(&lock, "zenoh")
+    );
+    println!(
+        "cargo:rustc-env=CDR_VERSION={}",
+        locked_version(&lock, "cdr")
+    );
+
+    if let Some(git_dir) = git(&["rev-parse", "--git-dir"]) {
+        let git_dir = Path::new(&git_dir);
+        println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+        println!("cargo:rerun-if-changed={}", git_dir.join("index").display());
+        if let Some(head_ref) = git(&["symbolic-ref", "-q", "HEAD"]) {
+            println!(
+                "cargo:rerun-if-changed={}",
+                git_dir.join(head_ref).display()
+            );
+        }
+    }
+    let commit = git(&["describe", "--always", "--dirty", "--abbrev=12"])
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_COMMIT={}", commit);
+}
+
+// The version of a package in a Cargo.lock file, where each package is described as:
+//   [[package]]
+//   name = "zenoh"
+//   version = "0.5.0-beta.9"
+fn locked_version(lock: &str, package: &str) -> String {
+    let name = format!("name = \"{}\"", package);
+    let mut lines = lock.lines();
+    while let Some(line) = lines.next() {
+        if line.trim() == name {
+            if let Some(version) = lines
+                .next()
+                .and_then(|l| l.trim().strip_prefix("version = "))
+            {
+                return version.trim_matches('"').to_string();
+            }
+        }
+    }
+    "unknown".to_string()
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
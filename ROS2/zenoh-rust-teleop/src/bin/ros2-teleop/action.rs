@@ -0,0 +1,320 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use cdr::{CdrLe, Infinite};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+use std::hash::{BuildHasher, Hasher};
+
+use crate::msgs::Time;
+
+// The keys to send a goal to turtlesim's rotate_absolute action, with the target theta (in radians)
+// (same layout than turtlesim's teleop_turtle_key)
+pub const GOAL_KEYS: [(char, f32); 8] = [
+    ('g', 0.0),
+    ('t', FRAC_PI_4),
+    ('r', FRAC_PI_2),
+    ('e', 3.0 * FRAC_PI_4),
+    ('d', PI),
+    ('c', -3.0 * FRAC_PI_4),
+    ('v', -FRAC_PI_2),
+    ('b', -FRAC_PI_4),
+];
+// The key to cancel the current goal
+pub const CANCEL_KEY: char = 'f';
+
+// action_msgs/msg/GoalStatus values
+const STATUS_SUCCEEDED: i8 = 4;
+const STATUS_CANCELED: i8 = 5;
+const STATUS_ABORTED: i8 = 6;
+
+// unique_identifier_msgs/UUID
+type Uuid = [u8; 16];
+
+// The header of the service requests and replies, as added by the CycloneDDS RMW in the payload
+// (the request is identified by the client GUID and a sequence number)
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy)]
+struct RequestHeader {
+    guid: u64,
+    seq: i64,
+}
+
+// turtlesim/action/RotateAbsolute_SendGoal_Request
+#[derive(Serialize)]
+struct SendGoalRequest {
+    header: RequestHeader,
+    goal_id: Uuid,
+    theta: f32,
+}
+
+// turtlesim/action/RotateAbsolute_SendGoal_Response
+#[derive(Deserialize)]
+struct SendGoalResponse {
+    header: RequestHeader,
+    accepted: bool,
+    _stamp: Time,
+}
+
+// turtlesim/action/RotateAbsolute_GetResult_Request
+#[derive(Serialize)]
+struct GetResultRequest {
+    header: RequestHeader,
+    goal_id: Uuid,
+}
+
+// turtlesim/action/RotateAbsolute_GetResult_Response
+#[derive(Deserialize)]
+struct GetResultResponse {
+    header: RequestHeader,
+    status: i8,
+    delta: f32,
+}
+
+#[derive(Serialize)]
+struct TimeMsg {
+    sec: i32,
+    nanosec: u32,
+}
+
+// action_msgs/srv/CancelGoal_Request
+#[derive(Serialize)]
+struct CancelGoalRequest {
+    header: RequestHeader,
+    goal_id: Uuid,
+    stamp: TimeMsg,
+}
+
+// action_msgs/srv/CancelGoal_Response (the list of canceling goals is ignored)
+#[derive(Deserialize)]
+struct CancelGoalResponse {
+    header: RequestHeader,
+    return_code: i8,
+}
+
+// turtlesim/action/RotateAbsolute_FeedbackMessage
+#[derive(Deserialize)]
+struct FeedbackMessage {
+    goal_id: Uuid,
+    remaining: f32,
+}
+
+struct Goal {
+    id: Uuid,
+    theta: f32,
+    send_goal_seq: i64,
+    get_result_seq: Option<i64>,
+}
+
+// An event of the goal in progress
+pub enum GoalEvent {
+    Accepted(f32),
+    Rejected(f32),
+    Feedback(f32),
+    Result(String),
+    CancelReply(String),
+}
+
+// The (key, payload) of a request to publish
+pub type Request = (String, Vec<u8>);
+
+// A client of turtlesim's rotate_absolute action, over the zenoh/DDS bridge.
+//
+// An action is made of 3 services and 2 topics (see https://design.ros2.org/articles/actions.html),
+// the bridge routing each service as a request topic ("rq/<action>/_action/<service>Request")
+// and a reply topic ("rr/<action>/_action/<service>Reply").
+// Once the goal is accepted, its result is requested and received when the goal completes.
+pub struct RotateAbsolute {
+    action: String,
+    guid: u64,
+    seq: i64,
+    goal: Option<Goal>,
+}
+
+impl RotateAbsolute {
+    pub fn new(action: String) -> Self {
+        RotateAbsolute {
+            action: action.trim_end_matches('/').to_string(),
+            guid: random_u64(),
+            seq: 0,
+            goal: None,
+        }
+    }
+
+    // The key expression of all the replies and topics of the action
+    pub fn subscription(&self) -> String {
+        format!("/*{}/_action/*", self.action)
+    }
+
+    // The keys of the requests published by the client
+    pub fn request_keys(&self) -> Vec<String> {
+        ["send_goal", "get_result", "cancel_goal"]
+            .iter()
+            .map(|service| self.request_key(service))
+            .collect()
+    }
+
+    fn next_header(&mut self) -> RequestHeader {
+        self.seq += 1;
+        RequestHeader {
+            guid: self.guid,
+            seq: self.seq,
+        }
+    }
+
+    fn request_key(&self, service: &str) -> String {
+        format!("/rq{}/_action/{}Request", self.action, service)
+    }
+
+    // The send_goal request for a new goal, replacing the current one
+    pub fn send_goal(&mut self, theta: f32) -> Request {
+        let header = self.next_header();
+        let mut id = [0u8; 16];
+        id[..8].copy_from_slice(&random_u64().to_le_bytes());
+        id[8..].copy_from_slice(&random_u64().to_le_bytes());
+        self.goal = Some(Goal {
+            id,
+            theta,
+            send_goal_seq: header.seq,
+            get_result_seq: None,
+        });
+        let request = SendGoalRequest {
+            header,
+            goal_id: id,
+            theta,
+        };
+        (
+            self.request_key("send_goal"),
+            cdr::serialize::<_, _, CdrLe>(&request, Infinite).unwrap(),
+        )
+    }
+
+    // The cancel_goal request for the current goal, if any
+    pub fn cancel_goal(&mut self) -> Option<Request> {
+        let goal_id = self.goal.as_ref()?.id;
+        let request = CancelGoalRequest {
+            header: self.next_header(),
+            goal_id,
+            stamp: TimeMsg { sec: 0, nanosec: 0 },
+        };
+        Some((
+            self.request_key("cancel_goal"),
+            cdr::serialize::<_, _, CdrLe>(&request, Infinite).unwrap(),
+        ))
+    }
+
+    // Process a sample received on the action's subscription, returning the corresponding event
+    // of the current goal (if any) and the request to send in response (if any).
+    pub fn on_sample(
+        &mut self,
+        res_name: &str,
+        payload: &[u8],
+    ) -> Result<(Option<GoalEvent>, Option<Request>), String> {
+        let prefix = format!("{}/_action/", self.action);
+        let (kind, name) = match res_name.get(3..).and_then(|n| n.strip_prefix(&prefix)) {
+            Some(name) => (&res_name[..3], name),
+            None => return Ok((None, None)),
+        };
+        match (kind, name) {
+            ("/rr", "send_goalReply") => {
+                let reply: SendGoalResponse = decode(payload)?;
+                let header = self.next_header();
+                match self.goal.as_mut() {
+                    Some(goal)
+                        if self.guid == reply.header.guid
+                            && goal.send_goal_seq == reply.header.seq =>
+                    {
+                        if reply.accepted {
+                            goal.get_result_seq = Some(header.seq);
+                            let request = GetResultRequest {
+                                header,
+                                goal_id: goal.id,
+                            };
+                            Ok((
+                                Some(GoalEvent::Accepted(goal.theta)),
+                                Some((
+                                    format!("/rq{}/_action/get_resultRequest", self.action),
+                                    cdr::serialize::<_, _, CdrLe>(&request, Infinite).unwrap(),
+                                )),
+                            ))
+                        } else {
+                            let theta = goal.theta;
+                            self.goal = None;
+                            Ok((Some(GoalEvent::Rejected(theta)), None))
+                        }
+                    }
+                    _ => Ok((None, None)),
+                }
+            }
+            ("/rr", "get_resultReply") => {
+                let reply: GetResultResponse = decode(payload)?;
+                match self.goal.as_ref() {
+                    Some(goal)
+                        if self.guid == reply.header.guid
+                            && goal.get_result_seq == Some(reply.header.seq) =>
+                    {
+                        let status = match reply.status {
+                            STATUS_SUCCEEDED => "succeeded",
+                            STATUS_CANCELED => "canceled",
+                            STATUS_ABORTED => "aborted",
+                            _ => "unknown status",
+                        };
+                        self.goal = None;
+                        Ok((
+                            Some(GoalEvent::Result(format!(
+                                "{} (rotated by {:.4} rad)",
+                                status, reply.delta
+                            ))),
+                            None,
+                        ))
+                    }
+                    _ => Ok((None, None)),
+                }
+            }
+            ("/rr", "cancel_goalReply") => {
+                let reply: CancelGoalResponse = decode(payload)?;
+                if reply.header.guid != self.guid {
+                    return Ok((None, None));
+                }
+                let result = match reply.return_code {
+                    0 => "accepted",
+                    1 => "rejected",
+                    2 => "unknown goal",
+                    3 => "goal terminated",
+                    _ => "unknown return code",
+                };
+                Ok((Some(GoalEvent::CancelReply(result.to_string())), None))
+            }
+            ("/rt", "feedback") => {
+                let feedback: FeedbackMessage = decode(payload)?;
+                match self.goal.as_ref() {
+                    Some(goal) if goal.id == feedback.goal_id => {
+                        Ok((Some(GoalEvent::Feedback(feedback.remaining)), None))
+                    }
+                    _ => Ok((None, None)),
+                }
+            }
+            // e.g. the status topic, or the requests
+            _ => Ok((None, None)),
+        }
+    }
+}
+
+fn decode<'de, T: serde::Deserialize<'de>>(payload: &[u8]) -> Result<T, String> {
+    cdr::deserialize_from::<_, T, _>(payload, Infinite)
+        .map_err(|e| format!("Error decoding action message: {}", e))
+}
+
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
@@ -0,0 +1,49 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::str::FromStr;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// The source of the timestamps of the recordings and traces (`--clock`):
+//  - System: the wall-clock time (seconds since the UNIX epoch), to correlate with the robot's logs
+//  - Steady: the monotonic time since startup, not affected by clock adjustments (for replay timing)
+#[derive(Debug, Clone, Copy)]
+pub enum Clock {
+    System,
+    Steady(Instant),
+}
+
+impl FromStr for Clock {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "system" => Ok(Clock::System),
+            "steady" => Ok(Clock::Steady(Instant::now())),
+            _ => Err(format!("Unknown clock: {}", s)),
+        }
+    }
+}
+
+impl Clock {
+    // The current timestamp, in seconds
+    pub fn now(&self) -> f64 {
+        match self {
+            Clock::System => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or(0.0),
+            Clock::Steady(start) => start.elapsed().as_secs_f64(),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crossterm::event::KeyCode;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+// The time a key stays active after being pressed or repeated, as the terminal reports no
+// key release (above the usual key repeat delays, as the deadman grace)
+const KEY_HOLD: Duration = Duration::from_millis(750);
+
+// The resolution of the commands of the inputs active together (`--conflict-policy`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    // an axis with opposing commands is stopped, the other axis keeps the latest command
+    Cancel,
+    // the latest input replaces the whole command
+    Last,
+    // the commands of the active inputs are added
+    Sum,
+}
+
+impl FromStr for ConflictPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cancel" => Ok(ConflictPolicy::Cancel),
+            "last" => Ok(ConflictPolicy::Last),
+            "sum" => Ok(ConflictPolicy::Sum),
+            _ => Err(format!("Unknown conflict policy: {}", s)),
+        }
+    }
+}
+
+// An input giving (forward, turn) commands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Input {
+    Key(KeyCode),
+    Joystick,
+    #[cfg(feature = "spacemouse")]
+    SpaceMouse,
+}
+
+// The inputs active together: the keys pressed within KEY_HOLD, and the joystick and SpaceMouse
+// while off-center. Their commands are resolved into one with the policy, before the transform.
+pub struct Conflicts {
+    policy: ConflictPolicy,
+    // the active inputs and their commands, the latest last
+    active: Vec<(Input, (f64, f64), Instant)>,
+}
+
+impl Conflicts {
+    pub fn new(policy: ConflictPolicy) -> Self {
+        Conflicts {
+            policy,
+            active: vec![],
+        }
+    }
+
+    // The resolved command on a command of the input
+    pub fn on_input(&mut self, input: Input, cmd: (f64, f64), now: Instant) -> (f64, f64) {
+        self.active.retain(|&(i, _, at)| {
+            i != input && (!matches!(i, Input::Key(_)) || now.duration_since(at) < KEY_HOLD)
+        });
+        // Note: an analog input back to the center is no more active
+        if matches!(input, Input::Key(_)) || cmd != (0.0, 0.0) {
+            self.active.push((input, cmd, now));
+        }
+        match self.policy {
+            ConflictPolicy::Last => cmd,
+            ConflictPolicy::Sum => self
+                .active
+                .iter()
+                .fold((0.0, 0.0), |sum, (_, c, _)| (sum.0 + c.0, sum.1 + c.1)),
+            ConflictPolicy::Cancel => (self.cancel_axis(|c| c.0), self.cancel_axis(|c| c.1)),
+        }
+    }
+
+    // Stop (e.g. on SPACE): no input stays active
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    // The latest command of the axis, or 0 if the active inputs oppose on it
+    fn cancel_axis<F: Fn((f64, f64)) -> f64>(&self, axis: F) -> f64 {
+        let values = self.active.iter().map(|&(_, c, _)| axis(c));
+        if values.clone().any(|v| v > 0.0) && values.clone().any(|v| v < 0.0) {
+            0.0
+        } else {
+            values.rev().find(|&v| v != 0.0).unwrap_or(0.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UP: (f64, f64) = (2.0, 0.0);
+    const DOWN: (f64, f64) = (-2.0, 0.0);
+    const LEFT: (f64, f64) = (0.0, 2.0);
+
+    #[test]
+    fn last_replaces_the_command() {
+        let mut conflicts = Conflicts::new(ConflictPolicy::Last);
+        let now = Instant::now();
+        assert_eq!(conflicts.on_input(Input::Key(KeyCode::Up), UP, now), UP);
+        assert_eq!(
+            conflicts.on_input(Input::Key(KeyCode::Down), DOWN, now),
+            DOWN
+        );
+        assert_eq!(
+            conflicts.on_input(Input::Key(KeyCode::Left), LEFT, now),
+            LEFT
+        );
+        assert_eq!(
+            conflicts.on_input(Input::Joystick, (0.0, 0.0), now),
+            (0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn cancel_stops_the_opposing_axis() {
+        let mut conflicts = Conflicts::new(ConflictPolicy::Cancel);
+        let now = Instant::now();
+        assert_eq!(conflicts.on_input(Input::Key(KeyCode::Up), UP, now), UP);
+        assert_eq!(
+            conflicts.on_input(Input::Key(KeyCode::Down), DOWN, now),
+            (0.0, 0.0)
+        );
+        // the turn doesn't conflict with the opposing keys
+        assert_eq!(
+            conflicts.on_input(Input::Key(KeyCode::Left), LEFT, now),
+            (0.0, 2.0)
+        );
+        // the joystick forward opposes the held DOWN key
+        assert_eq!(
+            conflicts.on_input(Input::Joystick, (1.0, 0.0), now),
+            (0.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn cancel_keeps_the_latest_command_without_opposition() {
+        let mut conflicts = Conflicts::new(ConflictPolicy::Cancel);
+        let now = Instant::now();
+        conflicts.on_input(Input::Key(KeyCode::Up), UP, now);
+        assert_eq!(
+            conflicts.on_input(Input::Joystick, (0.5, -1.0), now),
+            (0.5, -1.0)
+        );
+        // the joystick back to the center leaves the held key
+        assert_eq!(conflicts.on_input(Input::Joystick, (0.0, 0.0), now), UP);
+    }
+
+    #[test]
+    fn sum_adds_the_active_inputs() {
+        let mut conflicts = Conflicts::new(ConflictPolicy::Sum);
+        let now = Instant::now();
+        assert_eq!(conflicts.on_input(Input::Key(KeyCode::Up), UP, now), UP);
+        assert_eq!(
+            conflicts.on_input(Input::Key(KeyCode::Left), LEFT, now),
+            (2.0, 2.0)
+        );
+        assert_eq!(
+            conflicts.on_input(Input::Key(KeyCode::Down), DOWN, now),
+            (0.0, 2.0)
+        );
+        assert_eq!(
+            conflicts.on_input(Input::Joystick, (0.5, 0.0), now),
+            (0.5, 2.0)
+        );
+        // a repeated key isn't added twice
+        assert_eq!(
+            conflicts.on_input(Input::Key(KeyCode::Left), LEFT, now),
+            (0.5, 2.0)
+        );
+    }
+
+    #[test]
+    fn keys_expire_without_repeat() {
+        let mut conflicts = Conflicts::new(ConflictPolicy::Sum);
+        let now = Instant::now();
+        conflicts.on_input(Input::Key(KeyCode::Up), UP, now);
+        conflicts.on_input(Input::Joystick, (0.0, 1.0), now);
+        // the joystick stays active while off-center, the released key doesn't
+        assert_eq!(
+            conflicts.on_input(Input::Key(KeyCode::Left), LEFT, now + KEY_HOLD),
+            (0.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn clear_stops_all_inputs() {
+        let mut conflicts = Conflicts::new(ConflictPolicy::Sum);
+        let now = Instant::now();
+        conflicts.on_input(Input::Key(KeyCode::Up), UP, now);
+        conflicts.on_input(Input::Joystick, (0.0, 1.0), now);
+        conflicts.clear();
+        assert_eq!(
+            conflicts.on_input(Input::Key(KeyCode::Left), LEFT, now),
+            LEFT
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Instant;
+
+// A planar pose: position (in m) and heading (in rad, in ]-π, π])
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pose {
+    pub x: f64,
+    pub y: f64,
+    pub theta: f64,
+}
+
+impl fmt::Display for Pose {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "x: {:.2} y: {:.2} θ: {:.2}", self.x, self.y, self.theta)
+    }
+}
+
+impl FromStr for Pose {
+    type Err = String;
+
+    // Parse "x,y,theta"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| format!("Invalid --dead-reckoning pose '{}': {}", s, reason);
+        let values = s
+            .split(',')
+            .map(|v| v.trim().parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|e| invalid(&e.to_string()))?;
+        match values[..] {
+            [x, y, theta] if values.iter().all(|v| v.is_finite()) => Ok(Pose {
+                x,
+                y,
+                theta: normalize(theta),
+            }),
+            [_, _, _] => Err(invalid("the values must be finite")),
+            _ => Err(invalid("expected \"x,y,theta\"")),
+        }
+    }
+}
+
+// An angle normalized in ]-π, π]
+pub fn normalize(theta: f64) -> f64 {
+    let theta = theta.rem_euclid(2.0 * PI);
+    if theta > PI {
+        theta - 2.0 * PI
+    } else {
+        theta
+    }
+}
+
+// The estimate of the robot's pose (`--dead-reckoning`), integrating the published commands
+// over time as a unicycle. It drifts from the actual pose (slipping, acceleration, clamping by
+// the robot...), hence the reset to the origin when the robot is repositioned.
+pub struct DeadReckoning {
+    origin: Pose,
+    pose: Pose,
+    // the command being applied, and since when it's integrated
+    cmd: (f64, f64),
+    since: Instant,
+}
+
+impl DeadReckoning {
+    pub fn new(origin: Pose) -> Self {
+        DeadReckoning {
+            origin,
+            pose: origin,
+            cmd: (0.0, 0.0),
+            since: Instant::now(),
+        }
+    }
+
+    // The estimated pose, integrated up to now
+    pub fn pose(&mut self) -> Pose {
+        let now = Instant::now();
+        let dt = (now - self.since).as_secs_f64();
+        self.since = now;
+        let (linear, angular) = self.cmd;
+        let Pose { x, y, theta } = self.pose;
+        // Note: the exact arc of a constant command, and a straight line without rotation
+        self.pose = if angular.abs() < 1e-9 {
+            Pose {
+                x: x + linear * dt * theta.cos(),
+                y: y + linear * dt * theta.sin(),
+                theta,
+            }
+        } else {
+            let end = theta + angular * dt;
+            let radius = linear / angular;
+            Pose {
+                x: x + radius * (end.sin() - theta.sin()),
+                y: y - radius * (end.cos() - theta.cos()),
+                theta: normalize(end),
+            }
+        };
+        self.pose
+    }
+
+    // A command was published: the previous one is integrated up to now
+    pub fn on_command(&mut self, cmd: (f64, f64)) {
+        self.pose();
+        self.cmd = cmd;
+    }
+
+    // Reset the estimate to the origin, the current command being integrated from now
+    pub fn reset(&mut self) -> Pose {
+        self.since = Instant::now();
+        self.pose = self.origin;
+        self.pose
+    }
+}
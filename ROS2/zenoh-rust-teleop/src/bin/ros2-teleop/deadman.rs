@@ -0,0 +1,171 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::time::{Duration, Instant};
+
+// The period of the decaying commands
+const DECAY_PERIOD: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum State {
+    // No command to watch (stopped)
+    Idle,
+    // A command is held while keys are received (with the terminal's key repeat)
+    Holding,
+    // No key received during the grace period: the velocity decays to stop
+    Decaying(Instant),
+}
+
+// The deadman timer (`--deadman`, `--deadman-grace-ms` and `--deadman-decay-ms`): the robot stops when
+// no key is received anymore.
+//
+// The terminal doesn't report the key releases, so a held key is detected by its repeated events.
+// To not stop on a momentary gap, the velocity is held during the grace period after the last key,
+// then decays linearly to stop, during the decay period (immediate stop if 0).
+pub struct Deadman {
+    grace: Duration,
+    decay: Duration,
+    cmd: (f64, f64),
+    last_input: Instant,
+    next_decay: Instant,
+    state: State,
+}
+
+impl Deadman {
+    pub fn new(grace: Duration, decay: Duration) -> Self {
+        Deadman {
+            grace,
+            decay,
+            cmd: (0.0, 0.0),
+            last_input: Instant::now(),
+            next_decay: Instant::now(),
+            state: State::Idle,
+        }
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    // A command sent by a key
+    pub fn on_input(&mut self, cmd: (f64, f64)) {
+        self.cmd = cmd;
+        self.last_input = Instant::now();
+        self.state = if cmd == (0.0, 0.0) {
+            State::Idle
+        } else {
+            State::Holding
+        };
+    }
+
+    // Stop watching (e.g. for a command not sent by a key)
+    pub fn cancel(&mut self) {
+        self.state = State::Idle;
+    }
+
+    // The time of the next state transition or decaying command, if any
+    pub fn next_deadline(&self) -> Option<Instant> {
+        match self.state {
+            State::Idle => None,
+            State::Holding => Some(self.last_input + self.grace),
+            State::Decaying(_) => Some(self.next_decay),
+        }
+    }
+
+    // The command to publish at the deadline
+    pub fn on_deadline(&mut self) -> Option<(f64, f64)> {
+        let start = match self.state {
+            State::Idle => return None,
+            State::Holding => {
+                let now = Instant::now();
+                self.state = State::Decaying(now);
+                now
+            }
+            State::Decaying(start) => start,
+        };
+        let elapsed = start.elapsed();
+        if elapsed >= self.decay {
+            self.state = State::Idle;
+            return Some((0.0, 0.0));
+        }
+        self.next_decay = Instant::now() + DECAY_PERIOD;
+        let ratio = 1.0 - elapsed.as_secs_f64() / self.decay.as_secs_f64();
+        Some((self.cmd.0 * ratio, self.cmd.1 * ratio))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    const GRACE: Duration = Duration::from_millis(20);
+    const DECAY: Duration = Duration::from_millis(60);
+
+    #[test]
+    fn holds_during_the_grace_period() {
+        let mut deadman = Deadman::new(GRACE, DECAY);
+        let before = Instant::now();
+        deadman.on_input((2.0, -1.0));
+        assert_eq!(deadman.state(), State::Holding);
+        let deadline = deadman.next_deadline().unwrap();
+        assert!(deadline >= before + GRACE && deadline <= Instant::now() + GRACE);
+    }
+
+    #[test]
+    fn decays_linearly_then_stops() {
+        let mut deadman = Deadman::new(GRACE, DECAY);
+        deadman.on_input((2.0, -1.0));
+        // the 1st decaying command starts from the held velocity
+        let (linear, angular) = deadman.on_deadline().unwrap();
+        assert!(matches!(deadman.state(), State::Decaying(_)));
+        assert!(linear > 1.9 && linear <= 2.0);
+        assert!((angular * 2.0 + linear).abs() < 1e-9);
+        sleep(DECAY / 2);
+        let (linear, _) = deadman.on_deadline().unwrap();
+        assert!(linear > 0.0 && linear < 1.5);
+        sleep(DECAY);
+        assert_eq!(deadman.on_deadline(), Some((0.0, 0.0)));
+        assert_eq!(deadman.state(), State::Idle);
+        assert_eq!(deadman.next_deadline(), None);
+    }
+
+    #[test]
+    fn stops_immediately_without_decay() {
+        let mut deadman = Deadman::new(GRACE, Duration::ZERO);
+        deadman.on_input((2.0, 0.0));
+        assert_eq!(deadman.on_deadline(), Some((0.0, 0.0)));
+        assert_eq!(deadman.state(), State::Idle);
+    }
+
+    #[test]
+    fn input_during_the_decay_holds_again() {
+        let mut deadman = Deadman::new(GRACE, DECAY);
+        deadman.on_input((2.0, 0.0));
+        deadman.on_deadline();
+        deadman.on_input((1.0, 0.0));
+        assert_eq!(deadman.state(), State::Holding);
+    }
+
+    #[test]
+    fn stop_and_cancel_are_idle() {
+        let mut deadman = Deadman::new(GRACE, DECAY);
+        deadman.on_input((0.0, 0.0));
+        assert_eq!(deadman.state(), State::Idle);
+        assert_eq!(deadman.on_deadline(), None);
+        deadman.on_input((2.0, 0.0));
+        deadman.cancel();
+        assert_eq!(deadman.next_deadline(), None);
+        assert_eq!(deadman.on_deadline(), None);
+    }
+}
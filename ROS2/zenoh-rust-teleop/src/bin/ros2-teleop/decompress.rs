@@ -0,0 +1,53 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use ruzstd::streaming_decoder::StreamingDecoder;
+use std::io::Read;
+use std::str::FromStr;
+
+// The compression applied to the received payloads by a bandwidth-optimized bridge,
+// to be reverted before the CDR decoding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    // LZ4 frame format
+    Lz4,
+    Zstd,
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Compression::None),
+            "lz4" => Ok(Compression::Lz4),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(format!("Unknown compression: {}", s)),
+        }
+    }
+}
+
+impl Compression {
+    // A reader of the decompressed payload
+    // Note: the payload is decompressed on the fly, while being decoded.
+    pub fn reader<'a, R: Read + 'a>(&self, payload: R) -> Result<Box<dyn Read + 'a>, String> {
+        match self {
+            Compression::None => Ok(Box::new(payload)),
+            Compression::Lz4 => Ok(Box::new(lz4_flex::frame::FrameDecoder::new(payload))),
+            Compression::Zstd => StreamingDecoder::new(payload)
+                .map(|decoder| Box::new(decoder) as Box<dyn Read>)
+                .map_err(|e| format!("Error decompressing zstd payload: {}", e)),
+        }
+    }
+}
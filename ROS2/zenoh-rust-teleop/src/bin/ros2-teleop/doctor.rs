@@ -0,0 +1,262 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::future::timeout;
+use futures::prelude::*;
+use std::time::{Duration, Instant};
+use zenoh::net::*;
+use zenoh::Properties;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Verdict {
+    Pass,
+    Warn,
+    Fail,
+}
+
+// The result of a check of the `--doctor` report
+struct Check {
+    verdict: Verdict,
+    name: &'static str,
+    detail: String,
+}
+
+#[derive(Default)]
+struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn add(&mut self, verdict: Verdict, name: &'static str, detail: String) {
+        self.checks.push(Check {
+            verdict,
+            name,
+            detail,
+        });
+    }
+
+    // Print the report, returning false if any check failed
+    fn print(&self) -> bool {
+        println!("Doctor report:");
+        for check in self.checks.iter() {
+            let verdict = match check.verdict {
+                Verdict::Pass => "PASS",
+                Verdict::Warn => "WARN",
+                Verdict::Fail => "FAIL",
+            };
+            println!("  [{}] {}: {}", verdict, check.name, check.detail);
+        }
+        let count = |verdict| self.checks.iter().filter(|c| c.verdict == verdict).count();
+        println!(
+            "{} passed, {} warnings, {} failed",
+            count(Verdict::Pass),
+            count(Verdict::Warn),
+            count(Verdict::Fail)
+        );
+        count(Verdict::Fail) == 0
+    }
+}
+
+// Count the distinct peers and routers replying to the scouting during `duration`
+async fn scout_endpoints(
+    config: &Properties,
+    duration: Duration,
+) -> Result<(usize, usize), String> {
+    let mut receiver = scout(whatami::PEER | whatami::ROUTER, config.clone().into())
+        .await
+        .map_err(|e| format!("failed to scout: {}", e))?;
+    let mut pids: Vec<(String, ZInt)> = vec![];
+    let collect = async {
+        while let Some(hello) = receiver.next().await {
+            let pid = hello.pid.map(|pid| pid.to_string()).unwrap_or_default();
+            // Note: the same endpoint replies to each scout message
+            if !pids.iter().any(|(p, _)| *p == pid) {
+                pids.push((pid, hello.whatami.unwrap_or(whatami::ROUTER)));
+            }
+        }
+    };
+    let _ = timeout(duration, collect).await;
+    let routers = pids.iter().filter(|(_, w)| *w == whatami::ROUTER).count();
+    Ok((pids.len() - routers, routers))
+}
+
+// Query `selector`, returning the key of the first reply and its round trip time,
+// or None if no reply was received within `duration`
+async fn first_reply(
+    session: &Session,
+    selector: &str,
+    duration: Duration,
+) -> Result<Option<(String, Duration)>, String> {
+    let start = Instant::now();
+    let mut replies = session
+        .query(
+            &selector.into(),
+            "",
+            QueryTarget::default(),
+            QueryConsolidation::default(),
+        )
+        .await
+        .map_err(|e| format!("failed to query {}: {}", selector, e))?;
+    match timeout(duration, replies.next()).await {
+        Ok(Some(reply)) => Ok(Some((reply.data.res_name, start.elapsed()))),
+        _ => Ok(None),
+    }
+}
+
+// Run the `--doctor` checks, each one waiting at most `duration`, and print a pass/warn/fail report.
+// Returns false if any check failed.
+pub async fn run(config: Properties, cmd_vel: &str, rosout: &str, duration: Duration) -> bool {
+    let mut report = Report::default();
+
+    if config.get("multicast_scouting").map(String::as_str) == Some("false") {
+        report.add(
+            Verdict::Warn,
+            "scouting",
+            "skipped (multicast scouting is disabled)".to_string(),
+        );
+    } else {
+        println!("Scouting for {} ms...", duration.as_millis());
+        match scout_endpoints(&config, duration).await {
+            Ok((0, 0)) => report.add(
+                Verdict::Warn,
+                "scouting",
+                "no peer or router discovered (multicast may be blocked on this network)"
+                    .to_string(),
+            ),
+            Ok((peers, routers)) => report.add(
+                Verdict::Pass,
+                "scouting",
+                format!("discovered {} peers and {} routers", peers, routers),
+            ),
+            Err(e) => report.add(Verdict::Fail, "scouting", e),
+        }
+    }
+
+    println!("Opening session...");
+    let session = match open(config.into()).await {
+        Ok(session) => session,
+        Err(e) => {
+            report.add(Verdict::Fail, "session", format!("failed to open: {}", e));
+            return report.print();
+        }
+    };
+    // Note: the connections to the scouted endpoints are established in the background
+    async_std::task::sleep(duration.min(Duration::from_secs(1))).await;
+    let info = session.info().await;
+    let peers = crate::count_pids(info.get(&zenoh::net::info::ZN_INFO_PEER_PID_KEY));
+    let routers = crate::count_pids(info.get(&zenoh::net::info::ZN_INFO_ROUTER_PID_KEY));
+    if peers + routers == 0 {
+        report.add(
+            Verdict::Fail,
+            "session",
+            "opened, but not connected to any peer or router".to_string(),
+        );
+    } else {
+        report.add(
+            Verdict::Pass,
+            "session",
+            format!(
+                "opened, connected to {} peers and {} routers",
+                peers, routers
+            ),
+        );
+    }
+
+    // Note: zenoh 0.5 doesn't tell if a publication matches any subscriber.
+    // Instead, the admin space of the zenoh/DDS bridges is queried for a route of cmd_vel to DDS.
+    let selector = format!("/@/service/**/route/to_dds{}", cmd_vel);
+    match first_reply(&session, &selector, duration).await {
+        Ok(Some((key, _))) => report.add(
+            Verdict::Pass,
+            "cmd_vel",
+            format!("{} is routed to DDS ({})", cmd_vel, key),
+        ),
+        Ok(None) => report.add(
+            Verdict::Warn,
+            "cmd_vel",
+            format!(
+                "no DDS bridge routes {} (no ROS2 subscriber, or no bridge reachable)",
+                cmd_vel
+            ),
+        ),
+        Err(e) => report.add(Verdict::Fail, "cmd_vel", e),
+    }
+
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+        period: None,
+    };
+    match session.declare_subscriber(&rosout.into(), &sub_info).await {
+        Ok(mut subscriber) => {
+            let mut received = 0;
+            let receive = subscriber.receiver().for_each(|_| {
+                received += 1;
+                future::ready(())
+            });
+            let _ = timeout(duration, receive).await;
+            if received == 0 {
+                report.add(
+                    Verdict::Warn,
+                    "rosout",
+                    format!(
+                        "no log received on {} in {} ms",
+                        rosout,
+                        duration.as_millis()
+                    ),
+                );
+            } else {
+                report.add(
+                    Verdict::Pass,
+                    "rosout",
+                    format!(
+                        "{} logs received on {} in {} ms",
+                        received,
+                        rosout,
+                        duration.as_millis()
+                    ),
+                );
+            }
+        }
+        Err(e) => report.add(
+            Verdict::Fail,
+            "rosout",
+            format!("failed to subscribe to {}: {}", rosout, e),
+        ),
+    }
+
+    // Note: zenoh 0.5 doesn't provide a ping: the RTT is measured by a query to the routers' admin space
+    if routers == 0 {
+        report.add(
+            Verdict::Warn,
+            "rtt",
+            "skipped (not connected to any router)".to_string(),
+        );
+    } else {
+        match first_reply(&session, "/@/router/*", duration).await {
+            Ok(Some((key, rtt))) => report.add(
+                Verdict::Pass,
+                "rtt",
+                format!("{:.1} ms ({})", rtt.as_secs_f64() * 1000.0, key),
+            ),
+            Ok(None) => report.add(
+                Verdict::Warn,
+                "rtt",
+                format!("no router replied in {} ms", duration.as_millis()),
+            ),
+            Err(e) => report.add(Verdict::Fail, "rtt", e),
+        }
+    }
+
+    report.print()
+}
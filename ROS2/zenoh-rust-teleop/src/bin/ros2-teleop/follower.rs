@@ -0,0 +1,91 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// A follower robot, reproducing the commands published to the leader after a delay (convoy effect).
+//
+// Each command is queued with the time it's due, and is published to the follower's topic
+// when its deadline expires.
+pub struct Follower {
+    pub topic: String,
+    pub delay: Duration,
+    queue: VecDeque<(Instant, (f64, f64))>,
+}
+
+impl Follower {
+    pub fn new(topic: String, delay: Duration) -> Self {
+        Follower {
+            topic,
+            delay,
+            queue: VecDeque::new(),
+        }
+    }
+
+    // Queue a (linear, angular) command published to the leader
+    pub fn push(&mut self, cmd: (f64, f64)) {
+        self.queue.push_back((Instant::now() + self.delay, cmd));
+    }
+
+    // The deadline of the next queued command, if any
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.queue.front().map(|(deadline, _)| *deadline)
+    }
+
+    // The next command whose deadline expired, if any
+    pub fn pop_due(&mut self) -> Option<(f64, f64)> {
+        match self.queue.front() {
+            Some((deadline, _)) if *deadline <= Instant::now() => {
+                self.queue.pop_front().map(|(_, cmd)| cmd)
+            }
+            _ => None,
+        }
+    }
+
+    // Drop the queued commands, returning their number
+    pub fn clear(&mut self) -> usize {
+        let len = self.queue.len();
+        self.queue.clear();
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reproduces_the_commands_in_order_after_the_delay() {
+        let mut follower = Follower::new("/rt/turtle2/cmd_vel".to_string(), Duration::ZERO);
+        assert_eq!(follower.next_deadline(), None);
+        follower.push((1.0, 0.0));
+        follower.push((2.0, 0.5));
+        assert!(follower.next_deadline().unwrap() <= Instant::now());
+        assert_eq!(follower.pop_due(), Some((1.0, 0.0)));
+        assert_eq!(follower.pop_due(), Some((2.0, 0.5)));
+        assert_eq!(follower.pop_due(), None);
+    }
+
+    #[test]
+    fn keeps_the_commands_not_due() {
+        let mut follower =
+            Follower::new("/rt/turtle2/cmd_vel".to_string(), Duration::from_secs(60));
+        follower.push((1.0, 0.0));
+        assert_eq!(follower.pop_due(), None);
+        assert!(follower.next_deadline().unwrap() > Instant::now());
+        follower.push((2.0, 0.0));
+        assert_eq!(follower.clear(), 2);
+        assert_eq!(follower.next_deadline(), None);
+    }
+}
@@ -0,0 +1,114 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use cdr::Infinite;
+use std::f64::consts::FRAC_PI_2;
+use zenoh::net::ZBuf;
+
+use crate::dead_reckoning::normalize;
+use crate::msgs::Pose;
+
+// The minimum variation of a recomputed command to publish it again
+const MIN_CHANGE: f64 = 1e-3;
+
+// The driving in the world frame (`--frame world`): the operator's commands give a direction
+// in the world (forward: +y, left: -x, as seen from above with turtlesim), converted to the
+// robot's velocities with its heading received on the pose topic (turtlesim/Pose).
+//
+// The robot drives towards the direction at a speed decreasing with the heading error
+// (backwards if the direction is behind it), and turns to align with it. As the heading
+// changes, the command is recomputed on each pose received, until another command.
+pub struct WorldFrame {
+    pub topic: String,
+    // the robot's heading, if received
+    heading: Option<f64>,
+    // the operator's (forward, turn) command in the world frame, while it holds
+    input: Option<(f64, f64)>,
+}
+
+impl WorldFrame {
+    pub fn new(topic: String) -> Self {
+        WorldFrame {
+            topic,
+            heading: None,
+            input: None,
+        }
+    }
+
+    pub fn heading(&self) -> Option<f64> {
+        self.heading
+    }
+
+    pub fn on_sample(&mut self, payload: ZBuf) -> Result<(), String> {
+        let pose: Pose = cdr::deserialize_from(payload, Infinite)
+            .map_err(|e| format!("Error decoding Pose on {}: {}", self.topic, e))?;
+        self.heading = Some(pose.theta as f64);
+        Ok(())
+    }
+
+    // Convert the operator's command in the world frame to the robot's velocities,
+    // the scales giving the full speed and turn rate
+    pub fn command(&mut self, input: (f64, f64), scales: (f64, f64)) -> Result<(f64, f64), String> {
+        let heading = self
+            .heading
+            .ok_or_else(|| format!("no pose received on {} (world frame)", self.topic))?;
+        self.input = Some(input).filter(|&i| i != (0.0, 0.0));
+        Ok(to_robot(input, heading, scales))
+    }
+
+    // The command recomputed with the last heading, if the world command holds and it changed
+    pub fn update(&self, last_cmd: (f64, f64), scales: (f64, f64)) -> Option<(f64, f64)> {
+        let cmd = to_robot(self.input?, self.heading?, scales);
+        if (cmd.0 - last_cmd.0).abs() > MIN_CHANGE || (cmd.1 - last_cmd.1).abs() > MIN_CHANGE {
+            Some(cmd)
+        } else {
+            None
+        }
+    }
+
+    // End the world command (e.g. on another command)
+    pub fn clear(&mut self) {
+        self.input = None;
+    }
+}
+
+fn to_robot(
+    (forward, turn): (f64, f64),
+    heading: f64,
+    (linear_scale, angular_scale): (f64, f64),
+) -> (f64, f64) {
+    // The direction in the world, with the axes normalized by the scales
+    let axis = |value: f64, scale: f64| {
+        if scale == 0.0 {
+            0.0
+        } else {
+            value / scale.abs()
+        }
+    };
+    let (x, y) = (-axis(turn, angular_scale), axis(forward, linear_scale));
+    let magnitude = x.hypot(y).min(1.0);
+    if magnitude == 0.0 {
+        return (0.0, 0.0);
+    }
+    let error = normalize(y.atan2(x) - heading);
+    // Note: a direction behind the robot is reached backwards, rather than by a U-turn
+    let turn_error = if error.abs() > FRAC_PI_2 {
+        normalize(error + std::f64::consts::PI)
+    } else {
+        error
+    };
+    (
+        linear_scale.abs() * magnitude * error.cos(),
+        angular_scale.abs() * (turn_error / FRAC_PI_2),
+    )
+}
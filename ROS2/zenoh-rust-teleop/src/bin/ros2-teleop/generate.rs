@@ -0,0 +1,221 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use cdr::{CdrLe, Infinite};
+use futures::prelude::*;
+use std::f64::consts::PI;
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use zenoh::net::*;
+
+use crate::metrics::Metrics;
+use crate::msgs::{Log, Pose};
+use crate::qos::QosProfile;
+use crate::stamp::{self, Stamper};
+
+// The type of the generated messages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageType {
+    Twist,
+    Log,
+    Pose,
+}
+
+impl FromStr for MessageType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "twist" => Ok(MessageType::Twist),
+            "log" => Ok(MessageType::Log),
+            "pose" => Ok(MessageType::Pose),
+            _ => Err(format!("Unknown message type to generate: {}", s)),
+        }
+    }
+}
+
+// The shape of the generated values over time, within [-amplitude, amplitude]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pattern {
+    // a sawtooth, from -amplitude to amplitude over each period
+    Ramp,
+    Sine,
+    // uniformly distributed values
+    Random,
+}
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ramp" => Ok(Pattern::Ramp),
+            "sine" => Ok(Pattern::Sine),
+            "random" => Ok(Pattern::Random),
+            _ => Err(format!("Unknown generator pattern: {}", s)),
+        }
+    }
+}
+
+// The options of the `--generate` mode
+pub struct GenerateArgs {
+    pub message_type: MessageType,
+    pub topic: String,
+    pub pattern: Pattern,
+    pub rate: Duration,
+    pub amplitude: f64,
+    pub period: f64,
+    // the number of messages to publish before exiting, if limited
+    pub count: Option<u64>,
+}
+
+// A xorshift64* generator, good enough for synthetic data (and avoiding a dependency)
+struct XorShift(u64);
+
+impl XorShift {
+    fn new() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        XorShift(seed | 1)
+    }
+
+    // A value in [-1, 1]
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        let bits = self.0.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11;
+        bits as f64 / (1u64 << 52) as f64 - 1.0
+    }
+}
+
+struct Generator {
+    pattern: Pattern,
+    amplitude: f64,
+    period: f64,
+    random: XorShift,
+}
+
+impl Generator {
+    // The value at `t` seconds, the 2nd one shifted by a quarter of period
+    // (e.g. tracing a circle with the sine pattern)
+    fn values(&mut self, t: f64) -> (f64, f64) {
+        (self.value(t), self.value(t + self.period / 4.0))
+    }
+
+    fn value(&mut self, t: f64) -> f64 {
+        let phase = (t / self.period).rem_euclid(1.0);
+        self.amplitude
+            * match self.pattern {
+                Pattern::Ramp => 2.0 * phase - 1.0,
+                Pattern::Sine => (2.0 * PI * phase).sin(),
+                Pattern::Random => self.random.next(),
+            }
+    }
+}
+
+// Publish the synthetic messages at the configured rate, until the count is reached (or forever).
+// Returns false if a publication failed.
+pub async fn run(
+    session: &Session,
+    args: GenerateArgs,
+    stamper: Option<&Stamper>,
+    qos: QosProfile,
+    metrics: &Metrics,
+) -> bool {
+    let mut generator = Generator {
+        pattern: args.pattern,
+        amplitude: args.amplitude,
+        period: args.period,
+        random: XorShift::new(),
+    };
+    println!(
+        "Generating {:?} messages on {} ({:?} pattern, amplitude {}, period {} s) at {:.1} Hz...",
+        args.message_type,
+        args.topic,
+        args.pattern,
+        args.amplitude,
+        args.period,
+        1.0 / args.rate.as_secs_f64()
+    );
+    let key = ResKey::from(args.topic.as_str());
+    let start = Instant::now();
+    let mut ticks = async_std::stream::interval(args.rate);
+    let mut sent: u64 = 0;
+    let mut failed: u64 = 0;
+    while args.count.is_none_or(|count| sent + failed < count) {
+        let (a, b) = generator.values(start.elapsed().as_secs_f64());
+        let result = match args.message_type {
+            MessageType::Twist => crate::pub_twist(session, &key, a, b, stamper, qos, metrics)
+                .await
+                .map(|_| ()),
+            MessageType::Log => {
+                let log = Log {
+                    stamp: stamp::system_time(),
+                    level: 20,
+                    name: "generator".to_string(),
+                    msg: format!("synthetic value: {:.3}", a),
+                    file: file!().to_string(),
+                    function: "generate".to_string(),
+                    line: (sent + failed + 1) as u32,
+                };
+                write(session, &key, &log, qos, metrics).await
+            }
+            MessageType::Pose => {
+                // Note: the heading follows the direction of the values' variation
+                let (next_a, next_b) = generator.values(start.elapsed().as_secs_f64() + 0.01);
+                let pose = Pose {
+                    x: a as f32,
+                    y: b as f32,
+                    theta: (next_b - b).atan2(next_a - a) as f32,
+                    linear_velocity: 0.0,
+                    angular_velocity: 0.0,
+                };
+                write(session, &key, &pose, qos, metrics).await
+            }
+        };
+        match result {
+            Ok(()) => sent += 1,
+            Err(_) => failed += 1,
+        }
+        ticks.next().await;
+    }
+    println!("Generated {} messages ({} failed)", sent + failed, failed);
+    failed == 0
+}
+
+async fn write<T: serde::Serialize>(
+    session: &Session,
+    key: &ResKey,
+    msg: &T,
+    qos: QosProfile,
+    metrics: &Metrics,
+) -> Result<(), String> {
+    let encoded = cdr::serialize::<_, _, CdrLe>(msg, Infinite).unwrap();
+    session
+        .write_ext(
+            key,
+            encoded.into(),
+            encoding::APP_OCTET_STREAM,
+            data_kind::PUT,
+            qos.congestion_control,
+        )
+        .await
+        .map_err(|e| {
+            metrics.publish_errors.inc();
+            log::warn!("Error writing to zenoh: {}", e);
+            e.to_string()
+        })
+}
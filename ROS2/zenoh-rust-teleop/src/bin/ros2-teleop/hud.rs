@@ -0,0 +1,226 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crossterm::{
+    cursor::{MoveTo, RestorePosition, SavePosition},
+    queue,
+    style::Print,
+    terminal::{Clear, ClearType},
+};
+use std::collections::VecDeque;
+use std::io::{Stdout, Write};
+
+// The number of the last printed lines kept for the snapshots
+const HISTORY_LINES: usize = 100;
+
+// The status displayed in the status region of the terminal.
+#[derive(Default)]
+pub struct Status {
+    pub target: String,
+    pub linear: f64,
+    pub angular: f64,
+    pub peers: usize,
+    pub routers: usize,
+    pub logs_received: usize,
+    // the target topics whose last publication failed
+    pub failing: Vec<String>,
+    // the dead-reckoning estimate of the robot's pose, if enabled
+    pub pose: Option<String>,
+    // the frame of the commands, if not the robot's
+    pub frame: Option<String>,
+    // the QoS profile of the publications on cmd_vel
+    pub qos: String,
+    // whether the commands are disabled by the supervisor
+    pub disabled: bool,
+    pub message: String,
+}
+
+impl Status {
+    pub fn lines(&self) -> Vec<String> {
+        let mut first = format!(
+            "cmd_vel: {} ({}) | linear: {:.2} | angular: {:.2}",
+            self.target, self.qos, self.linear, self.angular
+        );
+        if let Some(pose) = self.pose.as_ref() {
+            first.push_str(&format!(" | pose: {}", pose));
+        }
+        if let Some(frame) = self.frame.as_ref() {
+            first.push_str(&format!(" | frame: {}", frame));
+        }
+        let mut lines = vec![
+            first,
+            if self.failing.is_empty() {
+                format!(
+                    "peers: {} | routers: {} | logs received: {}",
+                    self.peers, self.routers, self.logs_received
+                )
+            } else {
+                format!(
+                    "peers: {} | routers: {} | logs received: {} | FAILING: {}",
+                    self.peers,
+                    self.routers,
+                    self.logs_received,
+                    self.failing.join(", ")
+                )
+            },
+            self.message.clone(),
+        ];
+        if self.disabled {
+            lines[0] = format!("DISABLED BY SUPERVISOR | {}", lines[0]);
+        }
+        lines
+    }
+}
+
+// The terminal display, with the logs scrolling above a fixed status region
+// made of the bottom lines of the terminal.
+//
+// The logs scrolling is restricted above the status region using a scrolling region
+// (DECSTBM escape sequence). With 0 status lines, the logs are simply printed and no status
+// is displayed.
+// Note: as the terminal is in raw mode, each line must explicitly return to column 0.
+pub struct Hud {
+    stdout: Stdout,
+    status_lines: u16,
+    cols: u16,
+    rows: u16,
+    status: Vec<String>,
+    history: VecDeque<String>,
+}
+
+impl Hud {
+    pub fn new(status_lines: u16) -> Hud {
+        let (cols, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+        let mut hud = Hud {
+            stdout: std::io::stdout(),
+            status_lines,
+            cols,
+            rows,
+            status: vec![],
+            history: VecDeque::new(),
+        };
+        if hud.status_lines() > 0 {
+            // make room for the status region below the current content
+            for _ in 0..hud.status_lines() {
+                let _ = queue!(hud.stdout, Print("\r\n"));
+            }
+            hud.setup();
+        }
+        hud
+    }
+
+    // The actual number of status lines, keeping at least 1 line for the logs
+    fn status_lines(&self) -> u16 {
+        self.status_lines.min(self.rows.saturating_sub(1))
+    }
+
+    // The last row of the logs region
+    fn logs_bottom(&self) -> u16 {
+        self.rows - self.status_lines() - 1
+    }
+
+    fn setup(&mut self) {
+        let bottom = self.logs_bottom();
+        // Note: setting the scrolling region moves the cursor to home
+        let _ = queue!(
+            self.stdout,
+            Print(format!("\x1b[1;{}r", bottom + 1)),
+            MoveTo(0, bottom + 1),
+            Clear(ClearType::FromCursorDown),
+            MoveTo(0, bottom)
+        );
+        self.render();
+    }
+
+    // Print a line in the logs region, scrolling it up
+    pub fn println(&mut self, line: &str) {
+        if self.history.len() == HISTORY_LINES {
+            self.history.pop_front();
+        }
+        self.history.push_back(line.to_string());
+        if self.status_lines() == 0 {
+            let _ = queue!(self.stdout, Print(line), Print("\r\n"));
+        } else {
+            let bottom = self.logs_bottom();
+            let _ = queue!(self.stdout, MoveTo(0, bottom), Print("\r\n"), Print(line));
+        }
+        let _ = self.stdout.flush();
+    }
+
+    pub fn set_status(&mut self, status: &Status) {
+        self.status = status.lines();
+        self.render();
+    }
+
+    fn render(&mut self) {
+        let status_lines = self.status_lines();
+        if status_lines == 0 {
+            return;
+        }
+        let top = self.logs_bottom() + 1;
+        let _ = queue!(self.stdout, SavePosition);
+        for i in 0..status_lines {
+            let line = self
+                .status
+                .get(i as usize)
+                .map(String::as_str)
+                .unwrap_or("");
+            // truncate the line to the terminal width, to not wrap on the next line
+            let line: String = line.chars().take(self.cols as usize).collect();
+            let _ = queue!(
+                self.stdout,
+                MoveTo(0, top + i),
+                Clear(ClearType::CurrentLine),
+                Print(line)
+            );
+        }
+        let _ = queue!(self.stdout, RestorePosition);
+        let _ = self.stdout.flush();
+    }
+
+    // Write the last printed lines and the status to a text file,
+    // as displayed but without the terminal escape sequences
+    pub fn snapshot(&self, path: &str) -> std::io::Result<()> {
+        let mut file = std::fs::File::create(path)?;
+        for line in self.history.iter() {
+            writeln!(file, "{}", line)?;
+        }
+        writeln!(file, "{}", "-".repeat(self.cols as usize))?;
+        for line in self.status.iter() {
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.cols = cols;
+        self.rows = rows;
+        if self.status_lines() > 0 {
+            self.setup();
+        }
+    }
+
+    // Restore the whole terminal as scrolling region, leaving the status displayed
+    pub fn close(&mut self) {
+        if self.status_lines() > 0 {
+            let last_row = self.rows - 1;
+            let _ = queue!(
+                self.stdout,
+                Print("\x1b[r"),
+                MoveTo(0, last_row),
+                Print("\r\n")
+            );
+            let _ = self.stdout.flush();
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::time::{Duration, Instant};
+
+// The statistics of the intervals between the republished commands (`--jitter-stats`).
+//
+// Only the intervals ending with a republication are accumulated: those ending with a manual
+// command depend on the operator, not on the scheduling of the control loop.
+pub struct Jitter {
+    period: Duration,
+    last: Option<Instant>,
+    count: u64,
+    // the mean and the sum of the squared differences to the mean (Welford's algorithm), in seconds
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Jitter {
+    pub fn new(period: Duration) -> Self {
+        Jitter {
+            period,
+            last: None,
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: 0.0,
+        }
+    }
+
+    // A command was published (a republication if `repeated`)
+    pub fn record(&mut self, repeated: bool) {
+        let now = Instant::now();
+        if let (true, Some(last)) = (repeated, self.last) {
+            let interval = (now - last).as_secs_f64();
+            self.count += 1;
+            let delta = interval - self.mean;
+            self.mean += delta / self.count as f64;
+            self.m2 += delta * (interval - self.mean);
+            self.min = self.min.min(interval);
+            self.max = self.max.max(interval);
+        }
+        self.last = Some(now);
+    }
+
+    // The statistics, printed at exit
+    pub fn report(&self) -> Vec<String> {
+        if self.count == 0 {
+            return vec!["Jitter: no command republished".to_string()];
+        }
+        let ms = |secs: f64| secs * 1000.0;
+        let stddev = if self.count > 1 {
+            (self.m2 / (self.count - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        vec![
+            format!(
+                "Jitter over {} republications (period: {:.1} ms):",
+                self.count,
+                ms(self.period.as_secs_f64())
+            ),
+            format!(
+                "  interval mean: {:.2} ms, stddev: {:.2} ms, min: {:.2} ms, max gap: {:.2} ms",
+                ms(self.mean),
+                ms(stddev),
+                ms(self.min),
+                ms(self.max)
+            ),
+            format!(
+                "  max lateness: {:.2} ms",
+                ms(self.max - self.period.as_secs_f64())
+            ),
+        ]
+    }
+}
@@ -0,0 +1,162 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::channel::Sender;
+use async_std::fs::File;
+use futures::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::InputEvent;
+
+// See linux/joystick.h
+const JS_EVENT_BUTTON: u8 = 0x01;
+const JS_EVENT_AXIS: u8 = 0x02;
+const JS_EVENT_INIT: u8 = 0x80;
+
+const AXIS_MAX: f64 = 32767.0;
+const AXIS_MIN: f64 = -32768.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JoystickEvent {
+    // `init` is true for the synthetic events sent by the driver at opening,
+    // reporting the initial state of each axis.
+    Axis { number: u8, value: i16, init: bool },
+    Button { number: u8, pressed: bool },
+}
+
+// Read the events of a joystick device using the Linux joystick API (e.g. "/dev/input/js0"),
+// sending each to the input channel.
+pub async fn read_events(device: String, sender: Sender<InputEvent>) {
+    let mut file = match File::open(&device).await {
+        Ok(file) => file,
+        Err(e) => {
+            log::error!("Failed to open joystick device {}: {}", device, e);
+            return;
+        }
+    };
+    let mut buf = [0u8; 8];
+    loop {
+        if let Err(e) = file.read_exact(&mut buf).await {
+            log::error!("Failed to read joystick device {}: {}", device, e);
+            return;
+        }
+        // struct js_event { __u32 time; __s16 value; __u8 type; __u8 number; }
+        let value = i16::from_le_bytes([buf[4], buf[5]]);
+        let kind = buf[6];
+        let number = buf[7];
+        let event = if kind & JS_EVENT_AXIS != 0 {
+            JoystickEvent::Axis {
+                number,
+                value,
+                init: kind & JS_EVENT_INIT != 0,
+            }
+        } else if kind & JS_EVENT_BUTTON != 0 {
+            JoystickEvent::Button {
+                number,
+                pressed: value != 0,
+            }
+        } else {
+            continue;
+        };
+        if let Err(e) = sender.send(InputEvent::Joystick(event)).await {
+            log::warn!("Failed to push Joystick Event: {}", e);
+        }
+    }
+}
+
+// State of the joystick axes, with their calibration.
+//
+// The center of an axis is the raw value it reports at rest. It's subtracted from the raw value
+// before applying the deadzone and scaling, to compensate the sticks or triggers of miscalibrated
+// controllers that don't report 0 at rest (which otherwise makes the robot creep).
+// The centers are either configured, or captured from the initial state reported by the driver.
+pub struct Joystick {
+    raw: HashMap<u8, i16>,
+    centers: HashMap<u8, i16>,
+    deadzone: f64,
+}
+
+impl Joystick {
+    pub fn new(centers: HashMap<u8, i16>, deadzone: f64) -> Self {
+        Joystick {
+            raw: HashMap::new(),
+            centers,
+            deadzone,
+        }
+    }
+
+    // Record the raw value of an axis. Returns true if its center has been captured.
+    pub fn set_axis(&mut self, number: u8, value: i16, init: bool) -> bool {
+        self.raw.insert(number, value);
+        if init && !self.centers.contains_key(&number) {
+            self.centers.insert(number, value);
+            true
+        } else {
+            false
+        }
+    }
+
+    // Reset the calibration, capturing the current raw value of each axis as its center.
+    // The axes are assumed to be at rest.
+    pub fn recalibrate(&mut self) {
+        self.centers = self.raw.clone();
+    }
+
+    pub fn centers(&self) -> Centers<'_> {
+        Centers(&self.centers)
+    }
+
+    // The calibrated value of an axis, in [-1.0, 1.0]
+    pub fn axis(&self, number: u8) -> f64 {
+        let raw = *self.raw.get(&number).unwrap_or(&0) as f64;
+        let center = *self.centers.get(&number).unwrap_or(&0) as f64;
+        // scale each side of the center separately, so the full range of the axis is preserved
+        let value = if raw >= center {
+            if center < AXIS_MAX {
+                (raw - center) / (AXIS_MAX - center)
+            } else {
+                0.0
+            }
+        } else if center > AXIS_MIN {
+            (raw - center) / (center - AXIS_MIN)
+        } else {
+            0.0
+        };
+        apply_deadzone(value, self.deadzone)
+    }
+}
+
+// Zero a value within the deadzone, and rescale the remaining range to [-1.0, 1.0]
+pub fn apply_deadzone(value: f64, deadzone: f64) -> f64 {
+    if value.abs() <= deadzone {
+        0.0
+    } else {
+        value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+    }
+}
+
+// Displays the axes centers in the format accepted by the `--axis-center` option.
+pub struct Centers<'a>(&'a HashMap<u8, i16>);
+
+impl fmt::Display for Centers<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut axes: Vec<_> = self.0.iter().collect();
+        axes.sort();
+        let axes: Vec<String> = axes
+            .iter()
+            .map(|(number, center)| format!("{}={}", number, center))
+            .collect();
+        write!(f, "{}", axes.join(" "))
+    }
+}
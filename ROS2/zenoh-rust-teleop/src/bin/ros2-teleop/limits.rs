@@ -0,0 +1,162 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+
+// The velocity limits (`--max-linear` and `--max-angular`), as absolute values.
+//
+// The operator's commands are clamped to the limits, while the commands coming from
+// other sources (e.g. a rules file) are rejected if they exceed them.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub linear: f64,
+    pub angular: f64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            linear: f64::INFINITY,
+            angular: f64::INFINITY,
+        }
+    }
+}
+
+impl Limits {
+    pub fn clamp(&self, (linear, angular): (f64, f64)) -> (f64, f64) {
+        (
+            linear.clamp(-self.linear, self.linear),
+            angular.clamp(-self.angular, self.angular),
+        )
+    }
+
+    // Restrict the limits to the maxima advertised by the robot, returning a warning for each
+    // limit set by the operator above them
+    pub fn restrict(&mut self, robot: &Limits) -> Vec<String> {
+        let mut warnings = vec![];
+        for (name, limit, max) in [
+            ("--max-linear", &mut self.linear, robot.linear),
+            ("--max-angular", &mut self.angular, robot.angular),
+        ] {
+            if *limit > max {
+                if limit.is_finite() {
+                    warnings.push(format!(
+                        "{} {} exceeds the robot's advertised maximum {}: using {}",
+                        name, limit, max, max
+                    ));
+                }
+                *limit = max;
+            }
+        }
+        warnings
+    }
+
+    // Restrict the limits loaded from a preset to the operator's ones, returning a warning for each
+    // limit of the preset above them (including an unlimited one)
+    pub fn restrict_preset(&mut self, operator: &Limits) -> Vec<String> {
+        let mut warnings = vec![];
+        for (name, limit, max) in [
+            ("--max-linear", &mut self.linear, operator.linear),
+            ("--max-angular", &mut self.angular, operator.angular),
+        ] {
+            if *limit > max {
+                warnings.push(format!(
+                    "The preset's limit {} exceeds {} {}: using {}",
+                    limit, name, max, max
+                ));
+                *limit = max;
+            }
+        }
+        warnings
+    }
+
+    // Reject a NaN, infinite or out of bounds command
+    pub fn check(&self, (linear, angular): (f64, f64)) -> Result<(), String> {
+        if !linear.is_finite() || !angular.is_finite() {
+            Err(format!(
+                "invalid velocity (linear: {}, angular: {})",
+                linear, angular
+            ))
+        } else if linear.abs() > self.linear || angular.abs() > self.angular {
+            Err(format!(
+                "velocity out of bounds (linear: {}, angular: {}, limits: {}, {})",
+                linear, angular, self.linear, self.angular
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(linear: f64, angular: f64) -> Limits {
+        Limits { linear, angular }
+    }
+
+    #[test]
+    fn clamps_each_axis() {
+        let limits = limits(1.0, 0.5);
+        assert_eq!(limits.clamp((2.0, -2.0)), (1.0, -0.5));
+        assert_eq!(limits.clamp((-0.3, 0.2)), (-0.3, 0.2));
+        assert_eq!(Limits::default().clamp((1e9, -1e9)), (1e9, -1e9));
+    }
+
+    #[test]
+    fn rejects_invalid_and_out_of_bounds_commands() {
+        let limits = limits(1.0, 0.5);
+        assert!(limits.check((1.0, -0.5)).is_ok());
+        assert!(limits.check((1.1, 0.0)).is_err());
+        assert!(limits.check((0.0, -0.6)).is_err());
+        for invalid in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(Limits::default().check((invalid, 0.0)).is_err());
+            assert!(Limits::default().check((0.0, invalid)).is_err());
+        }
+    }
+
+    #[test]
+    fn restricts_to_the_robot_maxima() {
+        let mut operator = limits(3.0, 0.5);
+        let warnings = operator.restrict(&limits(2.0, 1.0));
+        assert_eq!((operator.linear, operator.angular), (2.0, 0.5));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("--max-linear 3"));
+    }
+
+    #[test]
+    fn presets_cant_exceed_the_operator_limits() {
+        let operator = limits(1.0, f64::INFINITY);
+        let mut preset = limits(5.0, 3.0);
+        let warnings = preset.restrict_preset(&operator);
+        assert_eq!((preset.linear, preset.angular), (1.0, 3.0));
+        assert_eq!(warnings.len(), 1);
+        // an unlimited preset's limit is restricted too
+        let mut preset = Limits::default();
+        let warnings = preset.restrict_preset(&operator);
+        assert_eq!((preset.linear, preset.angular), (1.0, f64::INFINITY));
+        assert_eq!(warnings.len(), 1);
+        // a lower limit is kept
+        let mut preset = limits(0.5, 3.0);
+        assert!(preset.restrict_preset(&operator).is_empty());
+        assert_eq!(preset.linear, 0.5);
+    }
+
+    #[test]
+    fn unset_limits_take_the_robot_maxima_silently() {
+        let mut operator = Limits::default();
+        let warnings = operator.restrict(&limits(2.0, 1.0));
+        assert_eq!((operator.linear, operator.angular), (2.0, 1.0));
+        assert!(warnings.is_empty());
+    }
+}
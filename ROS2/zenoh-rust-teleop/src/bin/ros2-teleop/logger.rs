@@ -0,0 +1,202 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use futures::prelude::*;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use zenoh::net::*;
+
+use crate::decompress::Compression;
+use crate::msgs::{Log, RosoutVersion};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown log format: {}", s)),
+        }
+    }
+}
+
+// The options of the `--logger-only` mode
+pub struct LoggerArgs {
+    pub topics: Vec<String>,
+    pub file: String,
+    pub format: Format,
+    // the minimum severity level of the persisted logs
+    pub level: u8,
+    pub rotate_size: Option<u64>,
+    pub rotate_age: Option<Duration>,
+    pub rotate_keep: usize,
+}
+
+// A log file rotated when its size or age exceeds a limit: FILE is renamed FILE.1,
+// FILE.1 is renamed FILE.2... up to FILE.<keep>, the oldest being removed.
+struct RotatingFile {
+    path: String,
+    file: File,
+    size: u64,
+    opened: Instant,
+    max_size: Option<u64>,
+    max_age: Option<Duration>,
+    keep: usize,
+}
+
+impl RotatingFile {
+    fn open(
+        path: &str,
+        max_size: Option<u64>,
+        max_age: Option<Duration>,
+        keep: usize,
+    ) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open log file {}: {}", path, e))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(RotatingFile {
+            path: path.to_string(),
+            file,
+            size,
+            opened: Instant::now(),
+            max_size,
+            max_age,
+            keep,
+        })
+    }
+
+    fn rotate(&mut self) -> Result<(), String> {
+        for i in (1..self.keep).rev() {
+            let from = format!("{}.{}", self.path, i);
+            if std::path::Path::new(&from).exists() {
+                std::fs::rename(&from, format!("{}.{}", self.path, i + 1))
+                    .map_err(|e| format!("Failed to rotate log file {}: {}", from, e))?;
+            }
+        }
+        if self.keep > 0 {
+            std::fs::rename(&self.path, format!("{}.1", self.path))
+                .map_err(|e| format!("Failed to rotate log file {}: {}", self.path, e))?;
+        }
+        *self = RotatingFile::open(&self.path, self.max_size, self.max_age, self.keep)?;
+        // Note: without any kept file, the log file is truncated
+        if self.keep == 0 {
+            self.file.set_len(0).map_err(|e| e.to_string())?;
+            self.size = 0;
+        }
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), String> {
+        let expired = self.max_size.is_some_and(|max| self.size >= max)
+            || self.max_age.is_some_and(|max| self.opened.elapsed() >= max);
+        if expired && self.size > 0 {
+            self.rotate()?;
+        }
+        // Note: each line is written at once, so no log is lost if the daemon is killed
+        writeln!(self.file, "{}", line)
+            .map_err(|e| format!("Failed to write log file {}: {}", self.path, e))?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+fn format_log(format: Format, topic: &str, log: &Log) -> String {
+    match format {
+        Format::Text => format!(
+            "[{}.{:09}] [{}] [{}]: {}",
+            log.stamp.sec,
+            log.stamp.nanosec,
+            log.level_name(),
+            log.name,
+            log.msg
+        ),
+        Format::Json => serde_json::json!({
+            "topic": topic,
+            "stamp": { "sec": log.stamp.sec, "nanosec": log.stamp.nanosec },
+            "level": log.level_name(),
+            "name": log.name,
+            "msg": log.msg,
+            "file": log.file,
+            "function": log.function,
+            "line": log.line,
+        })
+        .to_string(),
+    }
+}
+
+// Run as a daemon persisting the logs received on the rosout topics, until killed.
+pub async fn run(
+    session: &Session,
+    args: LoggerArgs,
+    decompress: Compression,
+    rosout_version: RosoutVersion,
+    size_limit: u64,
+) -> Result<(), String> {
+    let mut file = RotatingFile::open(
+        &args.file,
+        args.rotate_size,
+        args.rotate_age,
+        args.rotate_keep,
+    )?;
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+        period: None,
+    };
+    let mut subscribers = vec![];
+    for topic in args.topics.iter() {
+        println!("Subscriber on {}", topic);
+        subscribers.push(
+            session
+                .declare_subscriber(&topic.as_str().into(), &sub_info)
+                .await
+                .map_err(|e| format!("Failed to subscribe to {}: {}", topic, e))?,
+        );
+    }
+    println!("Logging to {} (Ctrl+C to stop)", args.file);
+
+    let mut samples = futures::stream::select_all(subscribers.iter_mut().map(|s| s.receiver()));
+    while let Some(sample) = samples.next().await {
+        if sample.payload.len() as u64 > size_limit {
+            log::warn!(
+                "Skipped Log of {} bytes on {}",
+                sample.payload.len(),
+                sample.res_name
+            );
+            continue;
+        }
+        let decoded = decompress
+            .reader(sample.payload)
+            .and_then(|reader| rosout_version.decode(reader, size_limit));
+        match decoded {
+            Ok(log) if log.level >= args.level => {
+                file.write_line(&format_log(args.format, &sample.res_name, &log))?
+            }
+            Ok(_) => (),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::collections::VecDeque;
+
+use crate::msgs::Log;
+
+// The layout of the displayed logs longer than `--max-log-width` columns (e.g. stack traces).
+//
+// The message is truncated with an ellipsis, or wrapped (`--wrap-logs`) on the next lines,
+// indented below the beginning of the message. The line breaks of a message are kept when
+// wrapping, and end the message when truncating.
+#[derive(Debug, Clone, Copy)]
+pub struct LogWidth {
+    pub max: usize,
+    pub wrap: bool,
+}
+
+impl LogWidth {
+    pub fn lines(&self, log: &Log) -> Vec<String> {
+        let prefix = log.prefix();
+        // Note: the indentation is limited, to leave room for the message on the wrapped lines
+        let indent = " ".repeat(prefix.chars().count().min(self.max / 2));
+        let mut msg_lines = log.msg.lines();
+        let first = format!("{}{}", prefix, msg_lines.next().unwrap_or(""));
+        if !self.wrap {
+            if first.chars().count() <= self.max && msg_lines.next().is_none() {
+                return vec![first];
+            }
+            let truncated: String = first.chars().take(self.max - 1).collect();
+            return vec![format!("{}…", truncated)];
+        }
+        let mut lines = vec![];
+        for (i, line) in std::iter::once(first.as_str()).chain(msg_lines).enumerate() {
+            let mut chars: Vec<char> = line.chars().collect();
+            // the 1st line starts with the prefix, the next ones are indented
+            let (mut start, mut width) = if i == 0 {
+                ("", self.max)
+            } else {
+                (indent.as_str(), self.max - indent.len())
+            };
+            loop {
+                let n = chars.len().min(width);
+                lines.push(format!("{}{}", start, chars.drain(..n).collect::<String>()));
+                if chars.is_empty() {
+                    break;
+                }
+                start = indent.as_str();
+                width = self.max - indent.len();
+            }
+        }
+        lines
+    }
+}
+
+// The log lines waiting to be displayed when the display is throttled (`--log-display-hz`).
+//
+// The received lines are buffered, and 1 line is displayed at each tick of the display timer.
+// When the buffer is full (`--log-backlog` lines), the oldest buffered line is dropped. The number
+// of dropped lines is displayed before the next buffered line.
+pub struct LogDisplay {
+    pending: VecDeque<String>,
+    capacity: usize,
+    dropped: usize,
+}
+
+impl LogDisplay {
+    pub fn new(capacity: usize) -> Self {
+        LogDisplay {
+            pending: VecDeque::with_capacity(capacity),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    pub fn push(&mut self, line: String) {
+        if self.pending.len() >= self.capacity {
+            self.pending.pop_front();
+            self.dropped += 1;
+        }
+        self.pending.push_back(line);
+    }
+
+    // The next line to display, if any
+    pub fn pop(&mut self) -> Option<String> {
+        if self.dropped > 0 {
+            let summary = format!("(-{} older log messages not displayed)", self.dropped);
+            self.dropped = 0;
+            return Some(summary);
+        }
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msgs::Time;
+
+    // A log whose prefix is "[1.2] [n]: " (11 characters)
+    fn log(msg: &str) -> Log {
+        Log {
+            stamp: Time { sec: 1, nanosec: 2 },
+            level: 20,
+            name: "n".to_string(),
+            msg: msg.to_string(),
+            file: String::new(),
+            function: String::new(),
+            line: 0,
+        }
+    }
+
+    #[test]
+    fn keeps_the_short_logs() {
+        let width = LogWidth {
+            max: 20,
+            wrap: false,
+        };
+        assert_eq!(width.lines(&log("short")), vec!["[1.2] [n]: short"]);
+    }
+
+    #[test]
+    fn truncates_at_a_char_boundary() {
+        let width = LogWidth {
+            max: 15,
+            wrap: false,
+        };
+        assert_eq!(width.lines(&log("éééééé")), vec!["[1.2] [n]: ééé…"]);
+        // the line breaks end the message
+        assert_eq!(width.lines(&log("ab\ncd")), vec!["[1.2] [n]: ab…"]);
+    }
+
+    #[test]
+    fn wraps_with_indentation() {
+        let width = LogWidth {
+            max: 15,
+            wrap: true,
+        };
+        assert_eq!(
+            width.lines(&log("ééééééé\nx")),
+            vec!["[1.2] [n]: éééé", "       ééé", "       x"]
+        );
+    }
+
+    #[test]
+    fn drops_the_oldest_lines_and_counts_them() {
+        let mut display = LogDisplay::new(2);
+        for line in ["a", "b", "c", "d"] {
+            display.push(line.to_string());
+        }
+        assert_eq!(
+            display.pop().as_deref(),
+            Some("(-2 older log messages not displayed)")
+        );
+        assert_eq!(display.pop().as_deref(), Some("c"));
+        display.push("e".to_string());
+        assert_eq!(display.pop().as_deref(), Some("d"));
+        assert_eq!(display.pop().as_deref(), Some("e"));
+        assert_eq!(display.pop(), None);
+    }
+}
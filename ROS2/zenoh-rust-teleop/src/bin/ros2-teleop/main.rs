@@ -0,0 +1,2606 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::channel::bounded;
+use async_std::sync::Arc;
+use cdr::{CdrLe, Infinite};
+use clap::{App, Arg};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use futures::prelude::*;
+use futures::select;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::Write;
+use std::time::{Duration, Instant};
+use zenoh::net::*;
+use zenoh::Properties;
+
+mod action;
+use action::{GoalEvent, RotateAbsolute};
+mod clock;
+use clock::Clock;
+mod conflict;
+use conflict::{ConflictPolicy, Conflicts, Input};
+mod dead_reckoning;
+use dead_reckoning::{DeadReckoning, Pose};
+mod deadman;
+use deadman::Deadman;
+mod decompress;
+use decompress::Compression;
+mod doctor;
+mod follower;
+use follower::Follower;
+mod frame;
+use frame::WorldFrame;
+mod generate;
+use generate::GenerateArgs;
+mod hud;
+use hud::{Hud, Status};
+mod jitter;
+use jitter::Jitter;
+mod joystick;
+use joystick::{Joystick, JoystickEvent};
+mod limits;
+use limits::Limits;
+mod logger;
+use logger::LoggerArgs;
+mod logs;
+use logs::{LogDisplay, LogWidth};
+mod msgs;
+use msgs::*;
+mod maneuver;
+use maneuver::{Maneuver, Recording};
+mod metrics;
+use metrics::Metrics;
+mod once;
+use once::ResultFormat;
+mod picker;
+mod preset;
+use preset::Preset;
+mod publishers;
+use publishers::Publishers;
+mod qos;
+use qos::{QosProfile, QosProfiles};
+mod record;
+use record::Recorder;
+mod remap;
+use remap::Remaps;
+mod robot_limits;
+mod rules;
+use rules::Rules;
+mod safety_override;
+use safety_override::Override;
+#[cfg(feature = "script")]
+mod script;
+#[cfg(feature = "spacemouse")]
+mod spacemouse;
+mod stamp;
+use stamp::Stamper;
+mod supervisor;
+use supervisor::Supervisor;
+mod targets;
+use targets::Targets;
+mod timeline;
+use timeline::{EventKind, Timeline};
+mod transform;
+use transform::Transform;
+mod triggers;
+use triggers::Trigger;
+mod typehash;
+mod verify;
+use verify::VerifyArgs;
+
+struct Args {
+    config: Properties,
+    cmd_vel: String,
+    rosout: String,
+    linear_scale: f64,
+    angular_scale: f64,
+    debug_input: Option<InputTrace>,
+    joystick: Option<JoystickArgs>,
+    // the SpaceMouse device and deadzone
+    #[cfg(feature = "spacemouse")]
+    spacemouse: Option<(String, f64)>,
+    status_lines: u16,
+    log_display_hz: Option<f64>,
+    log_backlog: usize,
+    log_width: Option<LogWidth>,
+    rules: Option<Rules>,
+    triggers: Vec<Trigger>,
+    decompress: Compression,
+    command_repeat: Option<Duration>,
+    rotate_absolute: Option<String>,
+    metrics_port: Option<u16>,
+    large_threshold: u64,
+    allow_large: bool,
+    follower: Option<Follower>,
+    pick_peer: Option<Duration>,
+    clock: Clock,
+    rosout_version: RosoutVersion,
+    recorder: Option<Recorder>,
+    stamper: Option<Stamper>,
+    clock_topic: Option<String>,
+    record_loop: bool,
+    limits: Limits,
+    deadman: Option<Deadman>,
+    show_keys: bool,
+    logger: Option<LoggerArgs>,
+    // the duration of each check of the `--doctor` mode
+    doctor: Option<Duration>,
+    // the (linear, angular) command of the `--once` mode
+    once: Option<(f64, f64)>,
+    result_format: ResultFormat,
+    qos: QosProfiles,
+    safety_override: Option<Override>,
+    jitter: Option<Jitter>,
+    // the topic of the velocity limits advertised by the robot, and the time to wait for them
+    robot_limits: Option<(String, Duration)>,
+    transform: Transform,
+    #[cfg(feature = "script")]
+    transform_script: Option<script::TransformScript>,
+    conflict_policy: ConflictPolicy,
+    snapshot_dir: String,
+    replay_verify: Option<VerifyArgs>,
+    dead_reckoning: Option<DeadReckoning>,
+    generate: Option<GenerateArgs>,
+    supervisor: Option<Supervisor>,
+    // the key of the shared preset
+    preset: Option<String>,
+    // the cmd_vel topics of the other robots receiving the same commands
+    broadcast: Vec<String>,
+    // whether to refuse to start if other teleops publish on cmd_vel
+    exclusive: bool,
+    timeline: Option<Timeline>,
+    world_frame: Option<WorldFrame>,
+}
+
+struct JoystickArgs {
+    device: String,
+    axis_linear: u8,
+    axis_angular: u8,
+    centers: HashMap<u8, i16>,
+    deadzone: f64,
+}
+
+// The events received from the input devices
+pub enum InputEvent {
+    Terminal(Event),
+    Joystick(JoystickEvent),
+    #[cfg(feature = "spacemouse")]
+    SpaceMouse(spacemouse::SpaceMouseEvent),
+}
+
+// Destination of the `--debug-input` traces: every event received from crossterm,
+// before any key binding is applied.
+enum InputTrace {
+    Stderr,
+    File(std::fs::File),
+}
+
+impl InputTrace {
+    fn trace(&mut self, clock: &Clock, ev: &Event) {
+        let line = match ev {
+            Event::Key(KeyEvent { code, modifiers }) => format!(
+                "[{:.3}] key: code={:?} modifiers={:?}",
+                clock.now(),
+                code,
+                modifiers
+            ),
+            _ => format!("[{:.3}] {:?}", clock.now(), ev),
+        };
+        // Note: in raw mode a new line doesn't return to column 0, hence the explicit '\r'
+        let res = match self {
+            InputTrace::Stderr => write!(std::io::stderr(), "{}\r\n", line),
+            InputTrace::File(file) => writeln!(file, "{}", line),
+        };
+        if let Err(e) = res {
+            log::warn!("Failed to trace input event: {}", e);
+        }
+    }
+}
+
+async fn pub_twist(
+    session: &Session,
+    cmd_key: &ResKey,
+    linear: f64,
+    angular: f64,
+    stamper: Option<&Stamper>,
+    qos: QosProfile,
+    metrics: &Metrics,
+) -> Result<usize, String> {
+    let encoded = encode_twist(linear, angular, stamper);
+    write_twist(session, cmd_key, encoded, qos, metrics).await
+}
+
+fn encode_twist(linear: f64, angular: f64, stamper: Option<&Stamper>) -> Vec<u8> {
+    let twist = Twist {
+        linear: Vector3 {
+            x: linear,
+            y: 0.0,
+            z: 0.0,
+        },
+        angular: Vector3 {
+            x: 0.0,
+            y: 0.0,
+            z: angular,
+        },
+    };
+
+    match stamper {
+        Some(stamper) => stamper.encode(twist),
+        None => cdr::serialize::<_, _, CdrLe>(&twist, Infinite).unwrap(),
+    }
+}
+
+async fn write_twist(
+    session: &Session,
+    cmd_key: &ResKey,
+    encoded: Vec<u8>,
+    qos: QosProfile,
+    metrics: &Metrics,
+) -> Result<usize, String> {
+    // Note: zenoh 0.5 doesn't provide an express (non-batched) option for publications.
+    // Anyway, an incomplete batch is sent as soon as no other message is being written.
+    // Returns the size of the published payload
+    let size = encoded.len();
+    match session
+        .write_ext(
+            cmd_key,
+            encoded.into(),
+            encoding::APP_OCTET_STREAM,
+            data_kind::PUT,
+            qos.congestion_control,
+        )
+        .await
+    {
+        Ok(()) => {
+            metrics.commands_sent.inc();
+            Ok(size)
+        }
+        Err(e) => {
+            metrics.publish_errors.inc();
+            log::warn!("Error writing to zenoh: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+#[async_std::main]
+async fn main() {
+    // Initiate logging
+    env_logger::init();
+
+    let Args {
+        mut config,
+        cmd_vel,
+        rosout,
+        mut linear_scale,
+        mut angular_scale,
+        mut debug_input,
+        joystick: joystick_args,
+        #[cfg(feature = "spacemouse")]
+            spacemouse: spacemouse_args,
+        status_lines,
+        log_display_hz,
+        log_backlog,
+        log_width,
+        mut rules,
+        mut triggers,
+        decompress,
+        command_repeat,
+        rotate_absolute,
+        metrics_port,
+        large_threshold,
+        allow_large,
+        mut follower,
+        pick_peer,
+        clock,
+        rosout_version,
+        mut recorder,
+        mut stamper,
+        clock_topic,
+        record_loop,
+        mut limits,
+        mut deadman,
+        show_keys,
+        logger,
+        doctor,
+        once,
+        result_format,
+        qos,
+        mut safety_override,
+        mut jitter,
+        robot_limits,
+        transform,
+        #[cfg(feature = "script")]
+        transform_script,
+        conflict_policy,
+        snapshot_dir,
+        replay_verify,
+        mut dead_reckoning,
+        generate,
+        mut supervisor,
+        preset,
+        broadcast,
+        exclusive,
+        mut timeline,
+        mut world_frame,
+    } = parse_args();
+
+    if let Some(duration) = pick_peer {
+        let locators = picker::pick_peer(&config, duration)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e));
+        // Only connect to the chosen endpoint, without discovering the others
+        config.insert("peer".to_string(), locators.join(","));
+        config.insert("multicast_scouting".to_string(), "false".to_string());
+    }
+
+    let metrics = Arc::new(Metrics::default());
+    if let Some(port) = metrics_port {
+        let listener = async_std::net::TcpListener::bind(("0.0.0.0", port))
+            .await
+            .unwrap_or_else(|e| panic!("Failed to listen on --metrics-port {}: {}", port, e));
+        println!("Metrics on http://0.0.0.0:{}/metrics", port);
+        async_std::task::spawn(metrics::serve(listener, metrics.clone()));
+    }
+
+    // Client of the rotate_absolute action
+    let mut rotate = rotate_absolute.map(RotateAbsolute::new);
+
+    if show_keys {
+        let mut keys = vec![
+            ("publication", cmd_vel.clone()),
+            ("subscription", rosout.clone()),
+        ];
+        if let Some(topic) = clock_topic.as_ref() {
+            keys.push(("subscription", topic.clone()));
+        }
+        if let Some(s) = supervisor.as_ref() {
+            keys.push(("subscription", s.topic.clone()));
+        }
+        if let Some(w) = world_frame.as_ref() {
+            keys.push(("subscription", w.topic.clone()));
+        }
+        if let Some(key) = preset.as_ref() {
+            keys.push(("publication", key.clone()));
+            keys.push(("query", key.clone()));
+        }
+        let announce = Publishers::new(&cmd_vel, "<session id>".to_string()).announce_key();
+        keys.push(("queryable", announce.clone()));
+        keys.push(("query", announce.replace("<session id>", "*")));
+        if let Some(f) = follower.as_ref() {
+            keys.push(("publication", f.topic.clone()));
+        }
+        for topic in broadcast.iter() {
+            keys.push(("publication", topic.clone()));
+        }
+        for trigger in triggers.iter() {
+            keys.push(("publication", trigger.topic.clone()));
+        }
+        if let Some(rotate) = rotate.as_ref() {
+            keys.push(("subscription", rotate.subscription()));
+            for key in rotate.request_keys() {
+                keys.push(("publication", key));
+            }
+        }
+        print_keys(&keys);
+    }
+
+    if let Some(duration) = doctor {
+        let passed = doctor::run(config, &cmd_vel, &rosout, duration).await;
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    let echo_config = replay_verify.as_ref().map(|_| config.clone());
+    println!("Opening session...");
+    let session = match open(config.into()).await {
+        Ok(session) => session,
+        // Note: the --once mode still prints its result, for the calling scripts
+        Err(e) => match once {
+            Some(cmd) => {
+                let error = format!("failed to open the zenoh session: {}", e);
+                once::print_result(
+                    result_format,
+                    &cmd_vel,
+                    limits.clamp(cmd),
+                    (0, 0),
+                    &Err(error),
+                );
+                std::process::exit(1);
+            }
+            None => panic!("Failed to open the zenoh session: {}", e),
+        },
+    };
+
+    if let Some(logger) = logger {
+        let size_limit = if allow_large {
+            u64::MAX
+        } else {
+            large_threshold
+        };
+        if let Err(e) = logger::run(&session, logger, decompress, rosout_version, size_limit).await
+        {
+            panic!("{}", e);
+        }
+        return;
+    }
+
+    if let Some(args) = generate {
+        let qos = qos.get(&args.topic);
+        let passed = generate::run(&session, args, stamper.as_ref(), qos, &metrics).await;
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    // The operator's limits (--max-linear and --max-angular), that a preset can't exceed
+    let cli_limits = limits;
+    // The maxima advertised by the robot, if received
+    let mut robot_max: Option<Limits> = None;
+    if let Some((topic, wait)) = robot_limits {
+        println!("Waiting for the robot's velocity limits on {}...", topic);
+        match robot_limits::receive(&session, &topic, wait).await {
+            Ok(robot) => {
+                for warning in limits.restrict(&robot) {
+                    log::warn!("{}", warning);
+                }
+                println!(
+                    "Velocity limits: linear {}, angular {} (robot's: {}, {})",
+                    limits.linear, limits.angular, robot.linear, robot.angular
+                );
+                if let Some(rules) = rules.as_ref() {
+                    if let Err(e) = rules.check(&limits) {
+                        panic!("{}", e);
+                    }
+                }
+                robot_max = Some(robot);
+            }
+            Err(e) => log::warn!(
+                "No velocity limits advertised by the robot ({}): using --max-linear and --max-angular",
+                e
+            ),
+        }
+    }
+
+    if let (Some(args), Some(echo_config)) = (replay_verify, echo_config) {
+        let passed = verify::run(
+            &session,
+            echo_config,
+            &cmd_vel,
+            args,
+            stamper.as_ref(),
+            qos.get(&cmd_vel),
+            &metrics,
+        )
+        .await;
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    if let Some(cmd) = once {
+        let cmd = limits.clamp(cmd);
+        let connected = once::wait_connection(&session, ONCE_CONNECTION_TIMEOUT).await;
+        let result = pub_twist(
+            &session,
+            &ResKey::from(cmd_vel.as_str()),
+            cmd.0,
+            cmd.1,
+            stamper.as_ref(),
+            qos.get(&cmd_vel),
+            &metrics,
+        )
+        .await;
+        // Note: the publication is sent asynchronously, so the session is closed to flush it
+        if let Err(e) = session.close().await {
+            log::warn!("Error closing zenoh session: {}", e);
+        }
+        let success = once::print_result(result_format, &cmd_vel, cmd, connected, &result);
+        std::process::exit(if success { 0 } else { 1 });
+    }
+
+    // Discovery of the other teleops publishing on cmd_vel, announcing this one
+    let pid = session
+        .info()
+        .await
+        .get(&zenoh::net::info::ZN_INFO_PID_KEY)
+        .cloned()
+        .unwrap_or_default();
+    let mut publishers = Publishers::new(&cmd_vel, pid);
+    match publishers.discover(&session).await {
+        Ok(others) if !others.is_empty() => {
+            if exclusive {
+                eprintln!(
+                    "Other teleops already publish on {} (sessions {}): not starting, as --exclusive",
+                    cmd_vel,
+                    others.join(", ")
+                );
+                std::process::exit(1);
+            }
+            log::warn!(
+                "Other teleops already publish on {} (sessions {}): their commands will conflict",
+                cmd_vel,
+                others.join(", ")
+            );
+            if let Some(t) = timeline.as_mut() {
+                t.event(
+                    EventKind::Safety,
+                    &format!("Other teleops publishing: sessions {}", others.join(", ")),
+                );
+            }
+        }
+        Ok(_) => (),
+        Err(e) => log::warn!("Failed to discover the other teleops: {}", e),
+    }
+    println!("Queryable on {}", publishers.announce_key());
+    let mut publishers_queryable = Some(
+        session
+            .declare_queryable(&publishers.announce_key().into(), queryable::EVAL)
+            .await
+            .unwrap(),
+    );
+
+    println!("Subscriber on {}", rosout);
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+        period: None,
+    };
+    let mut subscriber = Some(
+        session
+            .declare_subscriber(&rosout.clone().into(), &sub_info)
+            .await
+            .unwrap(),
+    );
+
+    // Subscriber of the rotate_absolute action
+    let mut action_subscriber = match rotate.as_ref() {
+        Some(rotate) => {
+            println!("Subscriber on {}", rotate.subscription());
+            Some(
+                session
+                    .declare_subscriber(&rotate.subscription().into(), &sub_info)
+                    .await
+                    .unwrap(),
+            )
+        }
+        None => None,
+    };
+
+    // Subscriber to the simulation clock, if used
+    let mut clock_subscriber = match clock_topic.as_ref() {
+        Some(topic) => {
+            println!("Subscriber on {}", topic);
+            Some(
+                session
+                    .declare_subscriber(&topic.as_str().into(), &sub_info)
+                    .await
+                    .unwrap(),
+            )
+        }
+        None => None,
+    };
+
+    // Subscriber to the supervisor's enable topic, if any
+    let mut enable_subscriber = match supervisor.as_ref() {
+        Some(s) => {
+            println!("Subscriber on {}", s.topic);
+            Some(
+                session
+                    .declare_subscriber(&s.topic.clone().into(), &sub_info)
+                    .await
+                    .unwrap(),
+            )
+        }
+        None => None,
+    };
+
+    // Subscriber to the robot's pose, for the world frame
+    let mut pose_subscriber = match world_frame.as_ref() {
+        Some(w) => {
+            println!("Subscriber on {}", w.topic);
+            Some(
+                session
+                    .declare_subscriber(&w.topic.clone().into(), &sub_info)
+                    .await
+                    .unwrap(),
+            )
+        }
+        None => None,
+    };
+
+    // ResKey for publication on "cmd_vel" topic
+    let cmd_key = ResKey::from(cmd_vel.clone());
+    let mut cmd_qos = qos.get(&cmd_vel);
+
+    // Keyboard event read loop, sending each to an async_std channel
+    // Note: enable raw mode for direct processing of key pressed, without having to hit ENTER...
+    // Unfortunately, this mode doesn't process new line characters on output. Thus all the
+    // output must go through the Hud, which explicitly returns to column 0 after each line.
+    crossterm::terminal::enable_raw_mode().unwrap();
+    let mut hud = Hud::new(status_lines);
+    let mut status = Status {
+        target: cmd_vel.clone(),
+        pose: dead_reckoning.as_mut().map(|d| d.pose().to_string()),
+        disabled: supervisor.is_some(),
+        frame: world_frame.as_ref().map(|_| "world (no pose)".to_string()),
+        qos: cmd_qos.to_string(),
+        ..Default::default()
+    };
+    hud.set_status(&status);
+
+    let (input_sender, input_receiver) = bounded::<InputEvent>(10);
+    let key_sender = input_sender.clone();
+    async_std::task::spawn(async move {
+        loop {
+            match crossterm::event::read() {
+                Ok(ev) => {
+                    if let Some(trace) = debug_input.as_mut() {
+                        trace.trace(&clock, &ev);
+                    }
+                    if let Err(e) = key_sender.send(InputEvent::Terminal(ev)).await {
+                        log::warn!("Failed to push Key Event: {}", e);
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Input error: {}", e);
+                }
+            }
+        }
+    });
+
+    // Joystick event read loop, sending each to the same channel
+    let mut joystick = joystick_args.map(|args| {
+        hud.println(&format!(
+            "Reading joystick {} (axis {} for linear, axis {} for angular)",
+            args.device, args.axis_linear, args.axis_angular
+        ));
+        async_std::task::spawn(joystick::read_events(args.device, input_sender.clone()));
+        (
+            Joystick::new(args.centers, args.deadzone),
+            args.axis_linear,
+            args.axis_angular,
+        )
+    });
+    // SpaceMouse event read loop, sending each to the same channel
+    #[cfg(feature = "spacemouse")]
+    let spacemouse_deadzone = spacemouse_args.map(|(device, deadzone)| {
+        hud.println(&format!("Reading SpaceMouse {}", device));
+        async_std::task::spawn(spacemouse::read_events(device, input_sender.clone()));
+        deadzone
+    });
+    let mut last_analog_cmd = (0.0, 0.0);
+    // The inputs active together, resolved with --conflict-policy
+    let mut conflicts = Conflicts::new(conflict_policy);
+
+    // Ticks to periodically refresh the connectivity status
+    let mut status_ticks = async_std::stream::interval(Duration::from_secs(1));
+    // Ticks to display the received logs, if throttled
+    let mut log_ticks =
+        log_display_hz.map(|hz| async_std::stream::interval(Duration::from_secs_f64(1.0 / hz)));
+    let mut log_display = LogDisplay::new(log_backlog);
+    // Whether the hint about a rosout layout mismatch has been displayed
+    let mut rosout_hint_displayed = false;
+    // The maneuver being played, if any
+    let mut maneuver: Option<Maneuver> = None;
+    // The recording of a maneuver to loop, if in progress
+    let mut recording: Option<Recording> = None;
+    // The last published command and the time of its next republication, if repeated
+    let mut last_cmd = (0.0, 0.0);
+    let mut next_repeat: Option<Instant> = None;
+    // The results of the publications to the leader and the follower
+    let mut targets = Targets::default();
+    // Whether the session has been connected to a peer or router, and since when it's disconnected
+    let mut connected_once = false;
+    let mut disconnected_since: Option<Instant> = None;
+
+    hud.println("Waiting commands with arrow keys or space bar to stop. Press on ESC, 'Q' or CTRL+C to quit.");
+    hud.println("Press CTRL+S to save a snapshot of the display to a text file.");
+    hud.println("Press CTRL+B to toggle the congestion control of the publications on cmd_vel (block or drop).");
+    if joystick.is_some() {
+        hud.println("Press CTRL+R to recalibrate the joystick centers (leave the sticks at rest).");
+    }
+    if record_loop {
+        hud.println("Press 'M' to start recording the commands, and again to stop and loop them until a command is sent.");
+    }
+    if rotate.is_some() {
+        hud.println("Press G/T/R/E/D/C/V/B to rotate to an absolute heading (rotate_absolute action), F to cancel.");
+    }
+    if let Some(follower) = follower.as_ref() {
+        hud.println(&format!(
+            "Follower on {}: reproducing the commands {} ms later.",
+            follower.topic,
+            follower.delay.as_millis()
+        ));
+    }
+    if let Some(w) = world_frame.as_ref() {
+        hud.println(&format!(
+            "World frame: the arrow keys give a direction in the world (UP: +y, LEFT: -x), with the heading received on {}.",
+            w.topic
+        ));
+    }
+    if !publishers.others().is_empty() {
+        hud.println(&format!(
+            "WARNING: other teleops (sessions {}) publish on {}: the commands will conflict",
+            publishers.others().join(", "),
+            cmd_vel
+        ));
+        status.message = format!(
+            "DUPLICATE PUBLISHERS: sessions {}",
+            publishers.others().join(", ")
+        );
+        hud.set_status(&status);
+    }
+    if let Some(s) = supervisor.as_ref() {
+        hud.println(&format!(
+            "The commands are disabled until the supervisor enables them on {}.",
+            s.topic
+        ));
+    }
+    if let Some(key) = preset.as_ref() {
+        hud.println(&format!(
+            "Press CTRL+P to store the scales and limits as preset on {}, CTRL+G to load the stored preset.",
+            key
+        ));
+    }
+    if dead_reckoning.is_some() {
+        hud.println("Press CTRL+Z to reset the dead-reckoning estimate of the pose (after repositioning the robot).");
+    }
+    if safety_override.is_some() {
+        hud.println("Press CTRL+O twice to lift the velocity limits and the deadman timer for a short time (CTRL+O again to end).");
+    }
+    for trigger in triggers.iter() {
+        hud.println(&format!(
+            "Press '{}' to publish on {}",
+            trigger.key.to_ascii_uppercase(),
+            trigger.topic
+        ));
+    }
+    if let Some(rules) = rules.as_ref() {
+        hud.println(&format!(
+            "{} rules loaded: matching logs will trigger maneuvers. Any command cancels a maneuver.",
+            rules.len()
+        ));
+    }
+    // Events management loop
+    loop {
+        // The (linear, angular) command to publish, if any
+        let mut cmd: Option<(f64, f64)> = None;
+        // Whether the safety override ended, restoring the limits
+        let mut override_ended = false;
+        // Whether a preset was loaded, changing the limits
+        let mut preset_loaded = false;
+        // Whether the command is a republication of the last one
+        let mut repeated = false;
+        // Whether the supervisor just disabled the commands, the robot being stopped
+        let mut supervisor_stop = false;
+        // Whether the command is an operator's command in the world frame
+        let mut world_cmd = false;
+        select!(
+            // On sample received by the subsriber
+            sample = next_sample(&mut subscriber).fuse() => {
+                let sample = sample.unwrap();
+                let size = sample.payload.len() as u64;
+                if size > large_threshold {
+                    status.message = format!("Large message on {}: {}{}", rosout, format_size(size),
+                        if allow_large { "" } else { " (skipped, see --allow-large)" });
+                    hud.set_status(&status);
+                }
+                // Note: the payload is read without copy, and the decoding is bounded by the
+                // threshold (after decompression) to prevent huge allocations
+                let size_limit = if allow_large { u64::MAX } else { large_threshold };
+                let decoded = if size > size_limit {
+                    Err(format!("Skipped Log of {} bytes", size))
+                } else {
+                    decompress.reader(sample.payload).and_then(|reader| rosout_version.decode(reader, size_limit))
+                };
+                // Note: a skipped large message is not suspicious
+                let suspicious = match &decoded {
+                    Ok(log) => !log.is_plausible(),
+                    Err(_) => size <= size_limit,
+                };
+                if suspicious && !rosout_hint_displayed {
+                    rosout_hint_displayed = true;
+                    hud.println(&format!(
+                        "Hint: a message on {} doesn't match the {:?} Log layout (see --rosout-version). Check that the topic \
+                        carries Log messages, or --decompress if the payloads are compressed.",
+                        rosout, rosout_version
+                    ));
+                }
+                match decoded {
+                    Ok(log) => {
+                        status.logs_received += 1;
+                        metrics.logs_received.inc();
+                        if let Some(t) = timeline.as_mut() {
+                            t.log(&log);
+                        }
+                        // Note: a maneuver in progress can't be interrupted by another rule
+                        if let (Some(rules), None) = (rules.as_mut(), maneuver.as_ref()) {
+                            if let Some(triggered) = rules.trigger(&log.msg) {
+                                if let Some(step) = triggered.current() {
+                                    cmd = Some((step.linear, step.angular));
+                                }
+                                status.message = format!("Maneuver: {}", triggered.progress());
+                                if let Some(t) = timeline.as_mut() {
+                                    t.event(EventKind::Maneuver, &format!("Triggered by a log: {}", triggered.progress()));
+                                }
+                                maneuver = Some(triggered);
+                            }
+                        }
+                        let lines = match log_width {
+                            Some(width) => width.lines(&log),
+                            None => vec![log.to_string()],
+                        };
+                        for line in lines {
+                            if log_ticks.is_some() {
+                                log_display.push(line);
+                            } else {
+                                hud.println(&line);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        metrics.decode_errors.inc();
+                        log::warn!("{}", e);
+                    }
+                }
+            },
+
+            // On logs display tick
+            _ = next_tick(&mut log_ticks).fuse() => {
+                if let Some(line) = log_display.pop() {
+                    hud.println(&line);
+                }
+            },
+
+            // On sample received by the action's subscriber
+            sample = next_sample(&mut action_subscriber).fuse() => {
+                if let (Some(sample), Some(rotate)) = (sample, rotate.as_mut()) {
+                    match rotate.on_sample(&sample.res_name, &sample.payload.contiguous()) {
+                        Ok((event, request)) => {
+                            if let Some((key, payload)) = request {
+                                if let Err(e) = session.write(&ResKey::from(key), payload.into()).await {
+                                    metrics.publish_errors.inc();
+                                    log::warn!("Error writing to zenoh: {}", e);
+                                }
+                            }
+                            if let Some(event) = event {
+                                status.message = match event {
+                                    GoalEvent::Accepted(theta) => format!("Rotate to {:.4}: goal accepted", theta),
+                                    GoalEvent::Rejected(theta) => format!("Rotate to {:.4}: goal rejected", theta),
+                                    GoalEvent::Feedback(remaining) => format!("Rotating: {:.4} rad remaining", remaining),
+                                    GoalEvent::Result(result) => format!("Rotation {}", result),
+                                    GoalEvent::CancelReply(result) => format!("Rotation cancel: {}", result),
+                                };
+                                hud.set_status(&status);
+                            }
+                        }
+                        Err(e) => {
+                            metrics.decode_errors.inc();
+                            log::warn!("{}", e);
+                        }
+                    }
+                }
+            },
+
+            // On sample received on the simulation clock topic
+            sample = next_sample(&mut clock_subscriber).fuse() => {
+                if let (Some(sample), Some(stamper)) = (sample, stamper.as_mut()) {
+                    match cdr::deserialize_from::<_, ClockMsg, _>(sample.payload, Infinite) {
+                        Ok(clock) => stamper.set_sim_time(clock.clock),
+                        Err(e) => {
+                            metrics.decode_errors.inc();
+                            log::warn!("Error decoding Clock: {}", e);
+                        }
+                    }
+                }
+            },
+
+            // On sample received on the supervisor's enable topic
+            sample = next_sample(&mut enable_subscriber).fuse() => {
+                if let (Some(sample), Some(s)) = (sample, supervisor.as_mut()) {
+                    match s.on_sample(sample.payload) {
+                        Ok(Some(true)) => {
+                            status.disabled = false;
+                            status.message = "Enabled by supervisor".to_string();
+                            if let Some(t) = timeline.as_mut() {
+                                t.event(EventKind::Safety, &status.message);
+                            }
+                            hud.set_status(&status);
+                        }
+                        Ok(Some(false)) => {
+                            // Note: the maneuver and the rotate goal are cancelled, and the follower stops
+                            // without reproducing the pending commands
+                            maneuver = None;
+                            if let Some((key, payload)) = rotate.as_mut().and_then(RotateAbsolute::cancel_goal) {
+                                if let Err(e) = session.write(&ResKey::from(key), payload.into()).await {
+                                    metrics.publish_errors.inc();
+                                    log::warn!("Error writing to zenoh: {}", e);
+                                }
+                            }
+                            if let Some(d) = deadman.as_mut() {
+                                d.cancel();
+                            }
+                            if let Some(f) = follower.as_mut() {
+                                f.clear();
+                                let result = pub_twist(&session, &ResKey::from(f.topic.as_str()), 0.0, 0.0, stamper.as_ref(), qos.get(&f.topic), &metrics).await;
+                                targets.record(&f.topic, &result);
+                            }
+                            supervisor_stop = true;
+                            cmd = Some((0.0, 0.0));
+                            status.disabled = true;
+                            status.message = "DISABLED BY SUPERVISOR: robot stopped".to_string();
+                            if let Some(t) = timeline.as_mut() {
+                                t.event(EventKind::Safety, &status.message);
+                            }
+                            hud.set_status(&status);
+                        }
+                        Ok(None) => (),
+                        Err(e) => {
+                            metrics.decode_errors.inc();
+                            log::warn!("{}", e);
+                        }
+                    }
+                }
+            },
+
+            // On query of another teleop starting to publish on cmd_vel
+            query = next_query(&mut publishers_queryable).fuse() => {
+                if let Some(pid) = query.and_then(|q| publishers.on_query(q, Instant::now())) {
+                    hud.println(&format!(
+                        "WARNING: another teleop (session {}) publishes on {}: the commands will conflict",
+                        pid, cmd_vel
+                    ));
+                    if let Some(t) = timeline.as_mut() {
+                        t.event(EventKind::Safety, &format!("Another teleop started publishing: session {}", pid));
+                    }
+                    status.message = format!("DUPLICATE PUBLISHERS: sessions {}", publishers.others().join(", "));
+                    hud.set_status(&status);
+                }
+            },
+
+            // On sample received on the robot's pose topic, for the world frame
+            sample = next_sample(&mut pose_subscriber).fuse() => {
+                if let (Some(sample), Some(w)) = (sample, world_frame.as_mut()) {
+                    match w.on_sample(sample.payload) {
+                        Ok(()) => {
+                            // Note: the status line is refreshed on the next tick or command
+                            if let Some(heading) = w.heading() {
+                                status.frame = Some(format!("world (heading: {:.2})", heading));
+                            }
+                            if let Some(c) = w.update(last_cmd, (linear_scale, angular_scale)) {
+                                cmd = Some(c);
+                                world_cmd = true;
+                            }
+                        }
+                        Err(e) => {
+                            metrics.decode_errors.inc();
+                            log::warn!("{}", e);
+                        }
+                    }
+                }
+            },
+
+            // On status refresh tick
+            _ = status_ticks.next().fuse() => {
+                let info = session.info().await;
+                status.peers = count_pids(info.get(&zenoh::net::info::ZN_INFO_PEER_PID_KEY));
+                status.routers = count_pids(info.get(&zenoh::net::info::ZN_INFO_ROUTER_PID_KEY));
+                metrics.peers.set(status.peers as u64);
+                metrics.routers.set(status.routers as u64);
+                let connected = status.peers + status.routers > 0;
+                match disconnected_since {
+                    Some(since) if connected => {
+                        disconnected_since = None;
+                        // Note: the subscriptions and the queryable are re-declared on recovery, rather than
+                        // relying on the zenoh routing to propagate them again to the peers and routers reconnected to
+                        redeclare_queryable(&session, &mut publishers_queryable, &publishers.announce_key()).await;
+                        redeclare(&session, &mut subscriber, &rosout, &sub_info).await;
+                        if let Some(rotate) = rotate.as_ref() {
+                            redeclare(&session, &mut action_subscriber, &rotate.subscription(), &sub_info).await;
+                        }
+                        if let Some(topic) = clock_topic.as_ref() {
+                            redeclare(&session, &mut clock_subscriber, topic, &sub_info).await;
+                        }
+                        if let Some(s) = supervisor.as_ref() {
+                            redeclare(&session, &mut enable_subscriber, &s.topic, &sub_info).await;
+                        }
+                        if let Some(w) = world_frame.as_ref() {
+                            redeclare(&session, &mut pose_subscriber, &w.topic, &sub_info).await;
+                        }
+                        let message = format!("Connection recovered after {} s", since.elapsed().as_secs());
+                        hud.println(&message);
+                        if let Some(t) = timeline.as_mut() {
+                            t.event(EventKind::Connectivity, &message);
+                        }
+                        status.message = "Connection recovered".to_string();
+                    }
+                    None if !connected && connected_once => {
+                        disconnected_since = Some(Instant::now());
+                        hud.println("Connection lost: waiting for a peer or router");
+                        if let Some(t) = timeline.as_mut() {
+                            t.event(EventKind::Connectivity, "Connection lost");
+                        }
+                        status.message = "CONNECTION LOST".to_string();
+                    }
+                    _ => (),
+                }
+                if connected && !connected_once {
+                    if let Some(t) = timeline.as_mut() {
+                        t.event(EventKind::Connectivity, &format!("Connected ({} peers, {} routers)", status.peers, status.routers));
+                    }
+                }
+                connected_once |= connected;
+                if let Err(e) = publishers.announce(&session, Instant::now()).await {
+                    log::warn!("Failed to announce this teleop: {}", e);
+                }
+                let expired = publishers.expire(Instant::now());
+                if !expired.is_empty() {
+                    hud.println(&format!("The teleops (sessions {}) stopped publishing on {}", expired.join(", "), cmd_vel));
+                    if let Some(t) = timeline.as_mut() {
+                        t.event(EventKind::Safety, &format!("Other teleops stopped publishing: sessions {}", expired.join(", ")));
+                    }
+                    if status.message.starts_with("DUPLICATE PUBLISHERS") {
+                        status.message = if publishers.others().is_empty() {
+                            String::new()
+                        } else {
+                            format!("DUPLICATE PUBLISHERS: sessions {}", publishers.others().join(", "))
+                        };
+                    }
+                }
+                status.pose = dead_reckoning.as_mut().map(|d| d.pose().to_string());
+                hud.set_status(&status);
+            },
+
+            // On command repeat deadline
+            _ = sleep_until(next_repeat).fuse() => {
+                cmd = Some(last_cmd);
+                repeated = true;
+            },
+
+            // On deadline of the follower's next command
+            _ = sleep_until(follower.as_ref().and_then(Follower::next_deadline)).fuse() => {
+                if let Some(f) = follower.as_mut() {
+                    while let Some((linear, angular)) = f.pop_due() {
+                        let result = pub_twist(&session, &ResKey::from(f.topic.as_str()), linear, angular, stamper.as_ref(), qos.get(&f.topic), &metrics).await;
+                        if targets.record(&f.topic, &result) {
+                            status.failing = targets.failing();
+                            hud.set_status(&status);
+                        }
+                    }
+                }
+            },
+
+            // On deadline of the deadman timer
+            _ = sleep_until(deadman.as_ref().and_then(Deadman::next_deadline)).fuse() => {
+                if let Some(d) = deadman.as_mut() {
+                    let was_decaying = matches!(d.state(), deadman::State::Decaying(_));
+                    cmd = d.on_deadline();
+                    status.message = match d.state() {
+                        deadman::State::Decaying(_) => "Deadman: no key received, slowing down".to_string(),
+                        _ => "Deadman: no key received, stopped".to_string(),
+                    };
+                    // Note: only the start of the decay and the stop are events, not each step
+                    let decaying = matches!(d.state(), deadman::State::Decaying(_));
+                    if let (Some(t), false) = (timeline.as_mut(), was_decaying && decaying) {
+                        t.event(EventKind::Safety, &status.message);
+                    }
+                }
+            },
+
+            // On deadline of the safety override (confirmation, banner blink or expiry)
+            _ = sleep_until(safety_override.as_ref().and_then(Override::next_deadline)).fuse() => {
+                if let Some(o) = safety_override.as_mut() {
+                    let was_active = o.is_active();
+                    o.on_deadline();
+                    status.message = match o.state() {
+                        safety_override::State::Off if was_active => {
+                            override_ended = true;
+                            "Override expired: safety limits restored".to_string()
+                        }
+                        safety_override::State::Off => "Override not confirmed".to_string(),
+                        _ => o.banner(),
+                    };
+                    if override_ended {
+                        if let Some(t) = timeline.as_mut() {
+                            t.event(EventKind::Safety, &status.message);
+                        }
+                    }
+                    hud.set_status(&status);
+                }
+            },
+
+            // On end of a maneuver step
+            _ = maneuver::step_end(&maneuver).fuse() => {
+                if let Some(m) = maneuver.as_mut() {
+                    match m.advance() {
+                        Some(step) => {
+                            cmd = Some((step.linear, step.angular));
+                            status.message = format!("Maneuver: {}", m.progress());
+                        }
+                        None => {
+                            cmd = Some((0.0, 0.0));
+                            status.message = format!("Maneuver complete: {}", m.progress());
+                            if let Some(t) = timeline.as_mut() {
+                                t.event(EventKind::Maneuver, &status.message);
+                            }
+                            maneuver = None;
+                        }
+                    }
+                }
+            },
+
+            // On joystick event received from the async_std channel
+            // Note: the events of the terminal are "forwarded" to the 2nd match below
+            input = input_receiver.recv().fuse() => {
+                // The looping maneuver started by the end of a recording, if any
+                let mut recorded: Option<Maneuver> = None;
+                let event = match input {
+                    Ok(InputEvent::Terminal(event)) => Ok(event),
+                    Ok(InputEvent::Joystick(event)) => {
+                        if let Some((joystick, axis_linear, axis_angular)) = joystick.as_mut() {
+                            if let JoystickEvent::Axis { number, value, init } = event {
+                                if joystick.set_axis(number, value, init) {
+                                    hud.println(&format!("Joystick axis {} center captured: {}", number, value));
+                                }
+                                // Note: stick's up and left are negative values
+                                let analog_cmd = (
+                                    -joystick.axis(*axis_linear) * linear_scale,
+                                    -joystick.axis(*axis_angular) * angular_scale,
+                                );
+                                if analog_cmd != last_analog_cmd {
+                                    last_analog_cmd = analog_cmd;
+                                    cmd = Some(conflicts.on_input(Input::Joystick, analog_cmd, Instant::now()));
+                                }
+                            }
+                        }
+                        Err(None)
+                    }
+                    #[cfg(feature = "spacemouse")]
+                    Ok(InputEvent::SpaceMouse(event)) => {
+                        if let Some(deadzone) = spacemouse_deadzone {
+                            // Note: pushing the cap forward and twisting it counterclockwise are negative values
+                            let analog_cmd = (
+                                -event.axis(1, deadzone) * linear_scale,
+                                -event.axis(5, deadzone) * angular_scale,
+                            );
+                            if analog_cmd != last_analog_cmd {
+                                last_analog_cmd = analog_cmd;
+                                cmd = Some(conflicts.on_input(Input::SpaceMouse, analog_cmd, Instant::now()));
+                            }
+                        }
+                        Err(None)
+                    }
+                    Err(e) => Err(Some(e)),
+                };
+                let key_event = matches!(event, Ok(Event::Key(_)));
+                // On keyboard event received from the async_std channel
+                // Note: the arrow keys active together are resolved with --conflict-policy
+                match event {
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Up, modifiers: _ })) => {
+                        cmd = Some(conflicts.on_input(Input::Key(KeyCode::Up), (linear_scale, 0.0), Instant::now()))
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Down, modifiers: _ })) => {
+                        cmd = Some(conflicts.on_input(Input::Key(KeyCode::Down), (-linear_scale, 0.0), Instant::now()))
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Left, modifiers: _ })) => {
+                        cmd = Some(conflicts.on_input(Input::Key(KeyCode::Left), (0.0, angular_scale), Instant::now()))
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Right, modifiers: _ })) => {
+                        cmd = Some(conflicts.on_input(Input::Key(KeyCode::Right), (0.0, -angular_scale), Instant::now()))
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char(' '), modifiers: _ })) => {
+                        conflicts.clear();
+                        cmd = Some((0.0, 0.0))
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Esc, modifiers: _ })) |
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('q'), modifiers: _ })) => {
+                        break
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('c'), modifiers })) if modifiers.contains(KeyModifiers::CONTROL) => {
+                        break
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('m'), modifiers: _ })) if record_loop => {
+                        match recording.take() {
+                            Some(r) => {
+                                let steps = r.stop();
+                                if steps.iter().map(|s| s.duration).sum::<f64>() < MIN_LOOP_DURATION {
+                                    status.message = "Recording discarded: too short to loop".to_string();
+                                } else {
+                                    recorded = Some(Maneuver::looping("recording".to_string(), steps));
+                                }
+                            }
+                            None => {
+                                recording = Some(Recording::start());
+                                status.message = "RECORDING".to_string();
+                            }
+                        }
+                        hud.set_status(&status);
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('s'), modifiers })) if modifiers.contains(KeyModifiers::CONTROL) => {
+                        let path = snapshot_path(&snapshot_dir);
+                        status.message = match hud.snapshot(&path) {
+                            Ok(()) => format!("Snapshot saved to {}", path),
+                            Err(e) => format!("Failed to save snapshot to {}: {}", path, e),
+                        };
+                        hud.set_status(&status);
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('z'), modifiers })) if dead_reckoning.is_some() && modifiers.contains(KeyModifiers::CONTROL) => {
+                        if let Some(d) = dead_reckoning.as_mut() {
+                            let pose = d.reset();
+                            status.pose = Some(pose.to_string());
+                            status.message = format!("Dead-reckoning reset to {}", pose);
+                            hud.set_status(&status);
+                        }
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('p'), modifiers })) if preset.is_some() && modifiers.contains(KeyModifiers::CONTROL) => {
+                        if let Some(key) = preset.as_ref() {
+                            let p = Preset::new((linear_scale, angular_scale), &limits);
+                            status.message = match preset::put(&session, key, &p).await {
+                                Ok(()) => format!("Preset stored on {}: {}", key, p),
+                                Err(e) => {
+                                    metrics.publish_errors.inc();
+                                    format!("Preset not stored: {}", e)
+                                }
+                            };
+                            hud.set_status(&status);
+                        }
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('b'), modifiers })) if modifiers.contains(KeyModifiers::CONTROL) => {
+                        // Note: the publications on cmd_vel are written with the profile, so
+                        // switching it doesn't need to re-declare anything
+                        cmd_qos = cmd_qos.toggled();
+                        status.qos = cmd_qos.to_string();
+                        status.message = format!("Congestion control toggled on {}: {}", cmd_vel, cmd_qos);
+                        hud.set_status(&status);
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('g'), modifiers })) if preset.is_some() && modifiers.contains(KeyModifiers::CONTROL) => {
+                        if let Some(key) = preset.as_ref() {
+                            // Note: the loaded limits are restricted to the operator's limits and the robot's maxima,
+                            // and must allow the rules' maneuvers
+                            status.message = match preset::get(&session, key).await {
+                                Ok(p) => {
+                                    let mut loaded = p.limits();
+                                    let mut warnings = loaded.restrict_preset(&cli_limits);
+                                    warnings.extend(robot_max.map(|robot| loaded.restrict(&robot)).unwrap_or_default());
+                                    match rules.as_ref().map_or(Ok(()), |rules| rules.check(&loaded)) {
+                                        Ok(()) => {
+                                            for warning in warnings {
+                                                log::warn!("{}", warning);
+                                            }
+                                            // the local inversions are kept
+                                            linear_scale = p.linear_scale.copysign(linear_scale);
+                                            angular_scale = p.angular_scale.copysign(angular_scale);
+                                            limits = loaded;
+                                            preset_loaded = true;
+                                            format!("Preset loaded from {}: {}", key, Preset::new((linear_scale, angular_scale), &limits))
+                                        }
+                                        Err(e) => format!("Preset rejected: {}", e),
+                                    }
+                                }
+                                Err(e) => format!("Preset not loaded: {}", e),
+                            };
+                            hud.set_status(&status);
+                        }
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('o'), modifiers })) if safety_override.is_some() && modifiers.contains(KeyModifiers::CONTROL) => {
+                        if let Some(o) = safety_override.as_mut() {
+                            let was_active = o.is_active();
+                            o.on_key();
+                            if was_active {
+                                override_ended = true;
+                                status.message = "Override ended: safety limits restored".to_string();
+                                if let Some(t) = timeline.as_mut() {
+                                    t.event(EventKind::Safety, &status.message);
+                                }
+                            } else {
+                                if o.is_active() {
+                                    if let Some(t) = timeline.as_mut() {
+                                        t.event(EventKind::Safety, "Override started: safety limits lifted");
+                                    }
+                                    if let Some(d) = deadman.as_mut() {
+                                        d.cancel();
+                                    }
+                                }
+                                status.message = o.banner();
+                            }
+                            hud.set_status(&status);
+                        }
+                    },
+                    // Note: 'R' is a rotate goal key, hence CTRL+R
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char('r'), modifiers })) if joystick.is_some() && modifiers.contains(KeyModifiers::CONTROL) => {
+                        if let Some((joystick, _, _)) = joystick.as_mut() {
+                            joystick.recalibrate();
+                            status.message = format!("Joystick recalibrated: {}", joystick.centers());
+                            hud.set_status(&status);
+                        }
+                    },
+                    // Note: the keys with CTRL or ALT are not bound to a goal or a trigger (e.g. CTRL+G without --preset)
+                    // Note: while disabled by the supervisor, the goals and the triggers are ignored (not the cancel)
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char(c), modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT }))
+                        if rotate.is_some() && is_action_key(c)
+                            && (c.to_ascii_lowercase() == action::CANCEL_KEY || supervisor.as_ref().is_none_or(Supervisor::is_enabled)) => {
+                        if let Some(rotate) = rotate.as_mut() {
+                            let c = c.to_ascii_lowercase();
+                            let request = match action::GOAL_KEYS.iter().find(|(key, _)| *key == c) {
+                                Some((_, theta)) => {
+                                    status.message = format!("Rotate to {:.4}: sending goal", theta);
+                                    Some(rotate.send_goal(*theta))
+                                }
+                                None => {
+                                    status.message = "Rotation: cancelling goal".to_string();
+                                    rotate.cancel_goal()
+                                }
+                            };
+                            if let Some((key, payload)) = request {
+                                if let Err(e) = session.write(&ResKey::from(key), payload.into()).await {
+                                    metrics.publish_errors.inc();
+                                    log::warn!("Error writing to zenoh: {}", e);
+                                }
+                            }
+                            hud.set_status(&status);
+                        }
+                    },
+                    Ok(Event::Key(KeyEvent { code: KeyCode::Char(c), modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT }))
+                        if supervisor.as_ref().is_none_or(Supervisor::is_enabled) => {
+                        if let Some(trigger) = triggers.iter_mut().find(|t| t.key == c.to_ascii_lowercase()) {
+                            let encoded = trigger.press();
+                            match session.write(&ResKey::from(trigger.topic.as_str()), encoded.into()).await {
+                                Ok(()) => status.message = format!("Trigger '{}': {}", trigger.key.to_ascii_uppercase(), trigger),
+                                Err(e) => {
+                                    metrics.publish_errors.inc();
+                                    log::warn!("Error writing to zenoh: {}", e);
+                                }
+                            }
+                            hud.set_status(&status);
+                        }
+                    },
+                    Ok(Event::Resize(cols, rows)) => hud.resize(cols, rows),
+                    Ok(_) => (),
+                    Err(Some(e)) => {
+                        log::warn!("Input error: {}", e);
+                    }
+                    Err(None) => (),
+                }
+                // The operator's commands are mapped to the velocities by the transform
+                cmd = cmd.map(|c| transform.apply(c));
+                // Note: the script's commands are still validated and clamped below
+                #[cfg(feature = "script")]
+                if let Some(script) = transform_script.as_ref() {
+                    cmd = cmd.and_then(|c| match script.apply(c) {
+                        Ok(c) => Some(c),
+                        Err(e) => {
+                            log::warn!("Command rejected: {}", e);
+                            if let Some(t) = timeline.as_mut() {
+                                t.event(EventKind::Safety, &format!("Command rejected: {}", e));
+                            }
+                            None
+                        }
+                    });
+                }
+                // In the world frame, the command is converted with the robot's heading
+                if let Some(w) = world_frame.as_mut() {
+                    cmd = cmd.and_then(|c| match w.command(c, (linear_scale, angular_scale)) {
+                        Ok(c) => {
+                            world_cmd = true;
+                            Some(c)
+                        }
+                        Err(e) => {
+                            log::warn!("Command ignored: {}", e);
+                            None
+                        }
+                    });
+                }
+                // A manual command cancels the maneuver in progress
+                if cmd.is_some() {
+                    if let Some(m) = maneuver.take() {
+                        status.message = format!("Maneuver cancelled: {}", m.progress());
+                        if let Some(t) = timeline.as_mut() {
+                            t.event(EventKind::Maneuver, &status.message);
+                        }
+                    }
+                }
+                // Note: the joystick's commands are not watched, as they are sent only on changes
+                // Note: the deadman timer is lifted by the safety override
+                let overridden = safety_override.as_ref().is_some_and(Override::is_active);
+                if let (Some(d), Some(c)) = (deadman.as_mut(), cmd) {
+                    if key_event && !overridden {
+                        d.on_input(c);
+                    } else {
+                        d.cancel();
+                    }
+                }
+                if let Some(m) = recorded {
+                    if let Some(step) = m.current() {
+                        cmd = Some((step.linear, step.angular));
+                    }
+                    status.message = m.progress();
+                    maneuver = Some(m);
+                }
+            }
+        );
+
+        // After a preset load, the last command is clamped to the new limits
+        // Note: it's already mapped (transform, world frame...), so it's only clamped again
+        if preset_loaded
+            && maneuver.is_none()
+            && cmd.is_none()
+            && limits.clamp(last_cmd) != last_cmd
+        {
+            cmd = Some(last_cmd);
+        }
+        // At the end of the safety override, the last command is clamped again and watched by the deadman timer
+        if override_ended && maneuver.is_none() {
+            if cmd.is_none() && limits.clamp(last_cmd) != last_cmd {
+                cmd = Some(last_cmd);
+            }
+            if let Some(d) = deadman.as_mut() {
+                d.on_input(limits.clamp(cmd.unwrap_or(last_cmd)));
+            }
+        }
+        let overridden = safety_override.as_ref().is_some_and(Override::is_active);
+        // Note: an invalid command (NaN or infinite) is rejected, as it can't be clamped
+        let mut clamped = false;
+        // Note: the override lifts the operator's limits, not the maxima advertised by the robot
+        let active_limits = if overridden {
+            robot_max.unwrap_or_default()
+        } else {
+            limits
+        };
+        let cmd = cmd.and_then(|cmd| match Limits::default().check(cmd) {
+            Ok(()) => {
+                let c = active_limits.clamp(cmd);
+                clamped = c != cmd;
+                Some(c)
+            }
+            Err(e) => {
+                log::warn!("Command rejected: {}", e);
+                if let Some(t) = timeline.as_mut() {
+                    t.event(EventKind::Safety, &format!("Command rejected: {}", e));
+                }
+                None
+            }
+        });
+        // While disabled by the supervisor, only the stop is published
+        let cmd = match cmd {
+            Some(_)
+                if !supervisor_stop && !supervisor.as_ref().is_none_or(Supervisor::is_enabled) =>
+            {
+                next_repeat = None;
+                if let Some(w) = world_frame.as_mut() {
+                    w.clear();
+                }
+                if !repeated {
+                    status.message = "DISABLED BY SUPERVISOR: command ignored".to_string();
+                    hud.set_status(&status);
+                }
+                None
+            }
+            cmd => cmd,
+        };
+        // The deadman timer doesn't apply to the maneuvers
+        if let (Some(d), Some(_)) = (deadman.as_mut(), maneuver.as_ref()) {
+            d.cancel();
+        }
+        if let Some((linear, angular)) = cmd {
+            // Note: a publication error is logged by write_twist
+            // Note: the broadcast targets receive the same message (with the same stamp)
+            let encoded = encode_twist(linear, angular, stamper.as_ref());
+            for topic in broadcast.iter() {
+                let result = write_twist(
+                    &session,
+                    &ResKey::from(topic.as_str()),
+                    encoded.clone(),
+                    qos.get(topic),
+                    &metrics,
+                )
+                .await;
+                targets.record(topic, &result);
+            }
+            let result = write_twist(&session, &cmd_key, encoded, cmd_qos, &metrics).await;
+            // Note: the status is updated below
+            targets.record(&cmd_vel, &result);
+            status.failing = targets.failing();
+            if let (Ok(_), Some(j)) = (result, jitter.as_mut()) {
+                j.record(repeated);
+            }
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.record(linear, angular);
+            }
+            if let Some(t) = timeline.as_mut() {
+                t.command((linear, angular), repeated, clamped);
+            }
+            // Note: the repeat deadline is reset by any publication, so a command and a repeat
+            // can't be published back-to-back
+            next_repeat = command_repeat.map(|period| Instant::now() + period);
+            last_cmd = (linear, angular);
+            // The world command holds until another command
+            if let (Some(w), false) = (world_frame.as_mut(), world_cmd || repeated) {
+                w.clear();
+            }
+            if let Some(f) = follower.as_mut() {
+                f.push(last_cmd);
+            }
+            if let Some(r) = recording.as_mut() {
+                r.record(last_cmd);
+                status.message = format!("RECORDING ({} steps)", r.len());
+            }
+            if let Some(d) = dead_reckoning.as_mut() {
+                d.on_command(last_cmd);
+                status.pose = Some(d.pose().to_string());
+            }
+            status.linear = linear;
+            status.angular = angular;
+            hud.set_status(&status);
+        }
+    }
+
+    // Stop robot at exit
+    let encoded = encode_twist(0.0, 0.0, stamper.as_ref());
+    for topic in broadcast.iter() {
+        let result = write_twist(
+            &session,
+            &ResKey::from(topic.as_str()),
+            encoded.clone(),
+            qos.get(topic),
+            &metrics,
+        )
+        .await;
+        targets.record(topic, &result);
+    }
+    let result = write_twist(&session, &cmd_key, encoded, cmd_qos, &metrics).await;
+    targets.record(&cmd_vel, &result);
+    if let Some(t) = timeline.as_mut() {
+        t.command((0.0, 0.0), false, false);
+    }
+    if let Some(mut recorder) = recorder {
+        recorder.record(0.0, 0.0);
+        recorder.close();
+    }
+    // The commands not yet reproduced by the follower are dropped: it stops immediately
+    if let Some(f) = follower.as_mut() {
+        f.clear();
+        let result = pub_twist(
+            &session,
+            &ResKey::from(f.topic.as_str()),
+            0.0,
+            0.0,
+            stamper.as_ref(),
+            qos.get(&f.topic),
+            &metrics,
+        )
+        .await;
+        targets.record(&f.topic, &result);
+    }
+    status.failing = targets.failing();
+    status.linear = 0.0;
+    status.angular = 0.0;
+    hud.set_status(&status);
+    hud.close();
+
+    crossterm::terminal::disable_raw_mode().unwrap();
+
+    // The publications summary, with several targets or on failures
+    if follower.is_some() || !broadcast.is_empty() || targets.has_failures() {
+        println!("Publications:");
+        for line in targets.summary() {
+            println!("{}", line);
+        }
+    }
+    if let Some(j) = jitter {
+        for line in j.report() {
+            println!("{}", line);
+        }
+    }
+    if let Some(mut t) = timeline {
+        t.event(EventKind::Safety, "Exit: robot stopped");
+        match t.write(&targets.summary()) {
+            Ok(path) => println!("Timeline report written to {}", path),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+}
+
+// The maximum time to wait for a peer or router before publishing the command of --once
+const ONCE_CONNECTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+// The minimum duration of a recording to loop (in seconds), preventing a busy loop
+const MIN_LOOP_DURATION: f64 = 0.1;
+
+// The minimum --max-log-width (the wrapped lines keeping at least half of it for the message)
+const MIN_LOG_WIDTH: usize = 20;
+
+// Wait for the next tick of an optional timer (forever if there is no timer)
+async fn next_tick(ticks: &mut Option<async_std::stream::Interval>) {
+    match ticks {
+        Some(ticks) => {
+            ticks.next().await;
+        }
+        None => future::pending().await,
+    }
+}
+
+// Wait until an optional deadline (forever if there is no deadline)
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => {
+            async_std::task::sleep(deadline.saturating_duration_since(Instant::now())).await
+        }
+        None => future::pending().await,
+    }
+}
+
+// Undeclare and declare again a subscriber, so that the subscription is propagated to the
+// peers and routers (a twin subscriber wouldn't be declared)
+async fn redeclare<'a>(
+    session: &'a Session,
+    subscriber: &mut Option<Subscriber<'a>>,
+    topic: &str,
+    sub_info: &SubInfo,
+) {
+    if let Some(s) = subscriber.take() {
+        if let Err(e) = s.undeclare().await {
+            log::warn!("Failed to undeclare the subscriber on {}: {}", topic, e);
+        }
+    }
+    match session.declare_subscriber(&topic.into(), sub_info).await {
+        Ok(s) => *subscriber = Some(s),
+        Err(e) => log::warn!("Failed to re-declare the subscriber on {}: {}", topic, e),
+    }
+}
+
+// Undeclare and declare again a queryable, as for the subscribers
+async fn redeclare_queryable<'a>(
+    session: &'a Session,
+    queryable: &mut Option<Queryable<'a>>,
+    key: &str,
+) {
+    if let Some(q) = queryable.take() {
+        if let Err(e) = q.undeclare().await {
+            log::warn!("Failed to undeclare the queryable on {}: {}", key, e);
+        }
+    }
+    match session
+        .declare_queryable(&key.into(), queryable::EVAL)
+        .await
+    {
+        Ok(q) => *queryable = Some(q),
+        Err(e) => log::warn!("Failed to re-declare the queryable on {}: {}", key, e),
+    }
+}
+
+// Wait for the next sample of an optional subscriber (forever if there is no subscriber)
+async fn next_sample(subscriber: &mut Option<Subscriber<'_>>) -> Option<Sample> {
+    match subscriber {
+        Some(subscriber) => subscriber.receiver().next().await,
+        None => future::pending().await,
+    }
+}
+
+// Wait for the next query of an optional queryable (forever if there is no queryable)
+async fn next_query(queryable: &mut Option<Queryable<'_>>) -> Option<Query> {
+    match queryable {
+        Some(queryable) => queryable.receiver().next().await,
+        None => future::pending().await,
+    }
+}
+
+fn is_action_key(c: char) -> bool {
+    let c = c.to_ascii_lowercase();
+    c == action::CANCEL_KEY || action::GOAL_KEYS.iter().any(|(key, _)| *key == c)
+}
+
+// A human-readable size (e.g. "1.5 MiB")
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["bytes", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+// Print the keys used for the publications and subscriptions (`--show-keys`)
+//
+// Note: zenoh-net uses the keys as is, while the zenoh API normalizes them as path expressions
+// (e.g. removing the duplicate and trailing '/'). As both don't match, the difference is highlighted.
+fn print_keys(keys: &[(&str, String)]) {
+    println!("Keys:");
+    for (usage, key) in keys.iter() {
+        match zenoh::PathExpr::try_from(key.as_str()) {
+            Ok(expr) if expr.as_str() == key => println!("  {}: {}", usage, key),
+            Ok(expr) => println!(
+                "  {}: {}  (WARNING: normalized by the zenoh API as {})",
+                usage, key, expr
+            ),
+            Err(e) => println!("  {}: {}  (WARNING: {})", usage, key, e),
+        }
+    }
+}
+
+// The file of a new snapshot of the display (CTRL+S), named after the current time
+fn snapshot_path(dir: &str) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    std::path::Path::new(dir)
+        .join(format!(
+            "ros2-teleop-{}.{:03}.txt",
+            now.as_secs(),
+            now.subsec_millis()
+        ))
+        .to_string_lossy()
+        .into_owned()
+}
+
+// Count the comma-separated zenoh ids of a session info property
+fn count_pids(pids: Option<&String>) -> usize {
+    pids.map(|pids| pids.split(',').filter(|pid| !pid.is_empty()).count())
+        .unwrap_or(0)
+}
+
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("GIT_COMMIT"), ")");
+// Note: ZENOH_VERSION, CDR_VERSION and GIT_COMMIT are provided by the build script
+const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    "\n  git commit: ",
+    env!("GIT_COMMIT"),
+    "\n  zenoh: ",
+    env!("ZENOH_VERSION"),
+    "\n  cdr: ",
+    env!("CDR_VERSION")
+);
+
+fn parse_args() -> Args {
+    let args = App::new("ros2-teleop")
+        .about("A ROS2 simple teleop app using Eclipse zenoh")
+        .version(VERSION)
+        .long_version(LONG_VERSION)
+        .arg(
+            Arg::from_usage("-m, --mode=[MODE]  'The zenoh session mode (peer by default).")
+                .possible_values(&["peer", "client"]),
+        )
+        .arg(Arg::from_usage(
+            "-e, --peer=[LOCATOR]...   'Peer locators used to initiate the zenoh session.'",
+        ))
+        .arg(Arg::from_usage(
+            "-l, --listener=[LOCATOR]...   'Locators to listen on.'",
+        ))
+        .arg(Arg::from_usage(
+            "-c, --config=[FILE]      'A configuration file.'",
+        ))
+        .arg(Arg::from_usage(
+            "--no-multicast-scouting 'Disable the multicast-based scouting mechanism.'",
+        ))
+        .arg(
+            Arg::from_usage("--cmd_vel=[topic] 'The 'cmd_vel' ROS2 topic'")
+                .default_value("/rt/turtle1/cmd_vel"),
+        )
+        .arg(
+            Arg::from_usage("--rosout=[topic] 'The 'rosout' ROS2 topic'")
+                .default_value("/rt/rosout"),
+        )
+        .arg(
+            Arg::from_usage("-a, --angular_scale=[FLOAT] 'The angular scale (a magnitude: see --invert-angular).'")
+                .default_value("2.0"),
+        )
+        .arg(Arg::from_usage("-x, --linear_scale=[FLOAT] 'The linear scale (a magnitude: see --invert-linear).'").default_value("2.0"))
+        .arg(Arg::from_usage(
+            "--invert-linear 'Invert the direction of the linear commands (keys, joystick and SpaceMouse).'",
+        ))
+        .arg(Arg::from_usage(
+            "--invert-angular 'Invert the direction of the angular commands (keys, joystick and SpaceMouse).'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "--debug-input=[FILE] 'Log every received input event (key code and modifiers) to FILE, or to stderr if no FILE is given.'",
+            )
+            .min_values(0),
+        )
+        .arg(
+            Arg::from_usage("--input=[INPUT] 'The input device used to drive the robot, in addition to the keyboard.'")
+                .possible_values(&["keyboard", "joystick", "spacemouse"])
+                .default_value("keyboard"),
+        )
+        .arg(
+            Arg::from_usage("--joystick-device=[DEVICE] 'The joystick device (Linux joystick API).'")
+                .default_value("/dev/input/js0"),
+        )
+        .arg(
+            Arg::from_usage("--spacemouse-device=[DEVICE] 'The SpaceMouse device (Linux hidraw).'")
+                .default_value("/dev/hidraw0"),
+        )
+        .arg(
+            Arg::from_usage("--axis-linear=[AXIS] 'The joystick axis driving the linear velocity.'")
+                .default_value("1"),
+        )
+        .arg(
+            Arg::from_usage("--axis-angular=[AXIS] 'The joystick axis driving the angular velocity.'")
+                .default_value("0"),
+        )
+        .arg(
+            Arg::from_usage("--deadzone=[FLOAT] 'The joystick deadzone, as a ratio of the axis range (in [0.0, 1.0[).'")
+                .default_value("0.1"),
+        )
+        .arg(Arg::from_usage(
+            "--axis-center=[AXIS=VALUE]... 'The raw value of a joystick axis at rest. By default, it's captured at startup.'",
+        ))
+        .arg(
+            Arg::from_usage("--status-lines=[N] 'The number of bottom lines of the terminal reserved for the status (0 to disable).'")
+                .default_value("3"),
+        )
+        .arg(Arg::from_usage(
+            "--log-display-hz=[HZ] 'The maximum number of received log lines displayed per second (not limited by default).'",
+        ))
+        .arg(Arg::from_usage(
+            "--max-log-width=[COLS] 'Truncate the displayed logs longer than COLS columns with an ellipsis (or wrap them with --wrap-logs).'",
+        ))
+        .arg(
+            Arg::from_usage("--wrap-logs 'Wrap the displayed logs longer than --max-log-width, below the beginning of the message.'")
+                .requires("max-log-width"),
+        )
+        .arg(
+            Arg::from_usage("--log-backlog=[N] 'With --log-display-hz, the maximum number of log lines waiting to be displayed (the oldest are dropped).'")
+                .default_value("16"),
+        )
+        .arg(Arg::from_usage(
+            "--rules=[FILE] 'A JSON file of rules triggering maneuvers when a received log message matches a regex.'",
+        ))
+        .arg(
+            Arg::from_usage("--rules-cooldown=[SECONDS] 'The default delay before a rule can be triggered again.'")
+                .default_value("5.0"),
+        )
+        .arg(Arg::from_usage(
+            "--trigger=[BINDING]... 'A key publishing a message on a topic when pressed, as KEY:TOPIC:TYPE[:VALUE] (e.g. \"h:/rt/horn:bool:toggle\"). TYPE is one of bool (VALUE: true, false or toggle), empty or string.'",
+        ))
+        .arg(
+            Arg::from_usage("--decompress=[COMPRESSION] 'The compression of the received payloads (by a bridge), reverted before decoding.'")
+                .possible_values(&["none", "lz4", "zstd"])
+                .default_value("none"),
+        )
+        .arg(Arg::from_usage(
+            "--command-repeat-hz=[HZ] 'Republish the last command at this rate, until another command is sent (not repeated by default).'",
+        ))
+        .arg(Arg::from_usage(
+            "-i, --interface=[IFACE] 'The network interface (name or IP address) used for multicast scouting and listening.'",
+        ))
+        .arg(
+            Arg::from_usage(
+                "--rotate-absolute=[ACTION] 'Enable the keys sending goals to turtlesim's rotate_absolute action (\"/turtle1/rotate_absolute\" if no ACTION is given).'",
+            )
+            .min_values(0),
+        )
+        .arg(Arg::from_usage(
+            "--metrics-port=[PORT] 'Expose metrics in Prometheus text format over HTTP on this port (on \"/metrics\").'",
+        ))
+        .arg(
+            Arg::from_usage("--large-threshold=[BYTES] 'The size above which a received message is considered large, and is not decoded unless --allow-large is set.'")
+                .default_value("1048576"),
+        )
+        .arg(Arg::from_usage(
+            "--allow-large 'Decode the received messages larger than --large-threshold (their size is displayed).'",
+        ))
+        .arg(Arg::from_usage(
+            "--follower=[TOPIC] 'The 'cmd_vel' topic of a follower robot, reproducing the commands after --follower-delay-ms.'",
+        ))
+        .arg(
+            Arg::from_usage("--follower-delay-ms=[MS] 'The delay before the follower reproduces a command.'")
+                .default_value("1000"),
+        )
+        .arg(Arg::from_usage(
+            "--broadcast=[TOPIC]... 'The 'cmd_vel' topic of another robot receiving the same commands, at the same time (e.g. for synchronized fleet moves).'",
+        ))
+        .arg(
+            Arg::from_usage("--broadcast-lead-ms=[MS] 'Stamp the commands with their execution time, MS after the current time, for the robots executing the stamped commands at their stamp.'")
+                .requires("stamped"),
+        )
+        .arg(
+            Arg::from_usage(
+                "--pick-peer=[MS] 'Scout for the zenoh peers and routers during MS milliseconds (1000 if not given), and connect only to the one chosen from the list.'",
+            )
+            .min_values(0)
+            .conflicts_with("no-multicast-scouting"),
+        )
+        .arg(
+            Arg::from_usage("--clock=[CLOCK] 'The source of the timestamps of --record and --debug-input: wall-clock (system) or monotonic since startup (steady).'")
+                .possible_values(&["system", "steady"])
+                .default_value("steady"),
+        )
+        .arg(Arg::from_usage(
+            "--record=[FILE] 'Record the published commands to FILE, timestamped with --clock (1 JSON object per line).'",
+        ))
+        .arg(
+            Arg::from_usage("--rosout-version=[DISTRO] 'The ROS2 distro of the robot, selecting the layout of the rosout messages (the same for all these distros).'")
+                .possible_values(&["foxy", "galactic", "humble", "iron", "jazzy"])
+                .default_value("humble"),
+        )
+        .arg(Arg::from_usage(
+            "--timeline-report=[FILE] 'Write a timeline report of the session to FILE on exit (markdown): the commands over time, the safety, connectivity and maneuver events, and the log severity counts.'",
+        ))
+        .arg(Arg::from_usage(
+            "--stamped 'Publish geometry_msgs/TwistStamped messages rather than Twist.'",
+        ))
+        .arg(
+            Arg::from_usage("--frame-id=[FRAME] 'The frame_id in the header of the stamped messages.'")
+                .default_value(""),
+        )
+        .arg(
+            Arg::from_usage("--header-seq 'Start the headers of the stamped messages with a sequence number increased by each message (the ROS1 std_msgs/Header layout, for older tools).'")
+                .requires("stamped"),
+        )
+        .arg(Arg::from_usage(
+            "--preset=[NAME] 'A preset shared with other teleops on the key /teleop/presets/NAME (stored by a zenoh storage): CTRL+P stores the scales and limits, CTRL+G loads them.'",
+        ))
+        .arg(Arg::from_usage(
+            "--enable-topic=[topic] 'A topic (std_msgs/Bool) of a supervisor enabling or disabling the commands. The commands are disabled until the 1st message, and disabling stops the robot.'",
+        ))
+        .arg(Arg::from_usage(
+            "--exclusive 'Refuse to start if other teleops already publish on the cmd_vel topic (by default, only warn).'",
+        ))
+        .arg(
+            Arg::from_usage("--use-sim-time 'Stamp the messages with the simulation time received on --clock-topic.'")
+                .requires("stamped"),
+        )
+        .arg(
+            Arg::from_usage("--clock-topic=[topic] 'The 'clock' ROS2 topic (rosgraph_msgs/Clock), with --use-sim-time.'")
+                .default_value("/rt/clock"),
+        )
+        .arg(Arg::from_usage(
+            "--remap=[FROM:=TO]... 'Remap a topic, given as ROS2 name (e.g. \"/turtle1/cmd_vel:=/robot1/cmd_vel\") or as zenoh key.'",
+        ))
+        .arg(Arg::from_usage(
+            "--record-loop 'Enable the key M to record the commands, then to loop them hands-free (e.g. for unattended demos).'",
+        ))
+        .arg(Arg::from_usage(
+            "--max-linear=[FLOAT] 'The maximum linear velocity (absolute value): the commands are clamped, and the rules exceeding it are rejected.'",
+        ))
+        .arg(Arg::from_usage(
+            "--max-angular=[FLOAT] 'The maximum angular velocity (absolute value): the commands are clamped, and the rules exceeding it are rejected.'",
+        ))
+        .arg(Arg::from_usage(
+            "--deadman 'Stop the robot when no key is received anymore (i.e. when the key is released, with the terminal's key repeat).'",
+        ))
+        .arg(
+            Arg::from_usage("--deadman-grace-ms=[MS] 'With --deadman, the delay after the last key during which the velocity is held.'")
+                .default_value("750"),
+        )
+        .arg(
+            Arg::from_usage("--deadman-decay-ms=[MS] 'With --deadman, the duration of the velocity decay to stop, after the grace delay (0 for an immediate stop).'")
+                .default_value("500"),
+        )
+        .arg(Arg::from_usage(
+            "--show-keys 'Print at startup the zenoh keys used for the publications and subscriptions.'",
+        ))
+        .arg(Arg::from_usage(
+            "--logger-only 'Run as a daemon persisting the logs received on --rosout and --logger-topic to --log-file (no teleop).'",
+        ))
+        .arg(Arg::from_usage(
+            "--logger-topic=[topic]... 'With --logger-only, another rosout-like topic to log.'",
+        ))
+        .arg(
+            Arg::from_usage("--log-file=[FILE] 'With --logger-only, the log file.'")
+                .default_value("rosout.log"),
+        )
+        .arg(
+            Arg::from_usage("--log-format=[FORMAT] 'With --logger-only, the format of the log file (1 line per log).'")
+                .possible_values(&["text", "json"])
+                .default_value("text"),
+        )
+        .arg(
+            Arg::from_usage("--log-level=[LEVEL] 'With --logger-only, the minimum severity level of the persisted logs.'")
+                .possible_values(&["debug", "info", "warn", "error", "fatal"])
+                .default_value("debug"),
+        )
+        .arg(Arg::from_usage(
+            "--log-rotate-size=[BYTES] 'With --logger-only, rotate the log file when its size exceeds BYTES.'",
+        ))
+        .arg(Arg::from_usage(
+            "--log-rotate-secs=[SECONDS] 'With --logger-only, rotate the log file every SECONDS.'",
+        ))
+        .arg(
+            Arg::from_usage("--log-rotate-keep=[N] 'With --logger-only, the number of rotated log files kept (FILE.1 to FILE.N).'")
+                .default_value("5"),
+        )
+        .arg(
+            Arg::from_usage("--doctor=[MS] 'Check the session, the scouting, the cmd_vel subscribers, the rosout logs and the RTT, waiting at most MS for each check (1000 by default), then print a report and exit (no teleop).'")
+                .min_values(0)
+                .conflicts_with("logger-only"),
+        )
+        .arg(
+            Arg::from_usage("--once [LINEAR] [ANGULAR] 'Publish a single command (linear and angular velocities, clamped to the limits) and exit (no teleop).'")
+                .allow_hyphen_values(true)
+                .conflicts_with_all(&["logger-only", "doctor"]),
+        )
+        .arg(
+            Arg::from_usage("--result-format=[FORMAT] 'With --once, the format of the result printed as the last line (json for the scripts).'")
+                .possible_values(&["text", "json"])
+                .default_value("text"),
+        )
+        .arg(Arg::from_usage(
+            "--qos=[TOPIC=OPTIONS]... 'The QoS profile of the publications to a target topic (--cmd_vel, --follower or --broadcast), as comma-separated options. Only congestion:block or congestion:drop (default) are supported by zenoh 0.5.'",
+        ))
+        .arg(Arg::from_usage(
+            "--override-secs=[SECONDS] 'Enable the safety override: pressing CTRL+O twice lifts the velocity limits and the deadman timer during SECONDS (at most 60).'",
+        ))
+        .arg(
+            Arg::from_usage("--jitter-stats 'Measure the intervals between the republished commands, and print their statistics at exit.'")
+                .requires("command-repeat-hz"),
+        )
+        .arg(
+            Arg::from_usage("--robot-limits=[topic] 'Read at startup the maximum velocities advertised by the robot on this topic (as a Twist), restricting --max-linear and --max-angular.'")
+                .min_values(0),
+        )
+        .arg(
+            Arg::from_usage("--robot-limits-wait-ms=[MS] 'With --robot-limits, the time to wait for the advertised velocities.'")
+                .default_value("2000"),
+        )
+        .arg(
+            Arg::from_usage("--transform=[MATRIX] 'The matrix mapping the input axes (forward, turn) to the published velocities (linear, angular), as \"ROW;ROW\" of comma-separated coefficients (e.g. \"1,0;0,-1\" to invert the turns).'")
+                .allow_hyphen_values(true)
+                .default_value("1,0;0,1"),
+        )
+        .arg(
+            Arg::from_usage("--conflict-policy=[POLICY] 'The resolution of the commands of the inputs active together (keys repeated within 750 ms, joystick or SpaceMouse off-center): the last input replaces the command (last), an axis with opposing commands stops (cancel), or the commands are added (sum).'")
+                .possible_values(&["cancel", "last", "sum"])
+                .default_value("last"),
+        )
+        .arg(Arg::from_usage(
+            "--transform-script=[FILE] 'A Rhai script transforming the input axes into the published velocities (after --transform), with a function transform(forward, turn) returning #{ linear: .., angular: .. }. Requires the script feature.'",
+        ))
+        .arg(
+            Arg::from_usage("--snapshot-dir=[DIR] 'The directory of the display snapshots saved with CTRL+S.'")
+                .default_value("."),
+        )
+        .arg(
+            Arg::from_usage("--replay-verify=[FILE] 'Replay the commands of a --record file, while echoing them from a 2nd session, then check that each one was observed with the same values and exit (no teleop).'")
+                .conflicts_with_all(&["logger-only", "doctor", "once"]),
+        )
+        .arg(
+            Arg::from_usage("--verify-tolerance=[FLOAT] 'With --replay-verify, the maximum difference between a recorded and an observed velocity.'")
+                .default_value("0.000001"),
+        )
+        .arg(
+            Arg::from_usage("--frame=[FRAME] 'The frame of the operator's commands: robot, or world to give a direction in the world, converted with the heading received on --pose-topic.'")
+                .possible_values(&["robot", "world"])
+                .default_value("robot"),
+        )
+        .arg(
+            Arg::from_usage("--pose-topic=[topic] 'The topic of the robot's pose (turtlesim/Pose), with --frame world.'")
+                .default_value("/rt/turtle1/pose"),
+        )
+        .arg(
+            Arg::from_usage("--dead-reckoning=[POSE] 'Estimate the robot's pose by integrating the published commands from POSE (\"x,y,theta\", \"0,0,0\" by default), displayed on the status line. CTRL+Z resets the estimate to POSE.'")
+                .min_values(0)
+                .allow_hyphen_values(true),
+        )
+        .arg(
+            Arg::from_usage("--generate=[TYPE] 'Publish synthetic messages of TYPE to test subscribers (no teleop): twist on --cmd_vel, log on /rt/rosout or pose (turtlesim/Pose) on /rt/turtle1/pose, unless --generate-topic.'")
+                .possible_values(&["twist", "log", "pose"])
+                .conflicts_with_all(&["logger-only", "doctor", "once", "replay-verify"]),
+        )
+        .arg(Arg::from_usage(
+            "--generate-topic=[topic] 'With --generate, the topic of the synthetic messages.'",
+        ))
+        .arg(
+            Arg::from_usage("--generate-pattern=[PATTERN] 'With --generate, the shape of the values over time, within [-amplitude, amplitude].'")
+                .possible_values(&["ramp", "sine", "random"])
+                .default_value("sine"),
+        )
+        .arg(
+            Arg::from_usage("--generate-hz=[HZ] 'With --generate, the rate of the messages.'")
+                .default_value("10"),
+        )
+        .arg(
+            Arg::from_usage("--generate-amplitude=[FLOAT] 'With --generate, the amplitude of the values.'")
+                .default_value("1.0"),
+        )
+        .arg(
+            Arg::from_usage("--generate-period=[SECONDS] 'With --generate, the period of the ramp and sine patterns.'")
+                .default_value("10"),
+        )
+        .arg(Arg::from_usage(
+            "--generate-count=[N] 'With --generate, exit after publishing N messages.'",
+        ))
+        .arg(Arg::from_usage(
+            "--type-hash=[HASH] 'The type hash of the Twist topics (as shown by \"ros2 topic info --verbose\"), for the bridges including the type and its hash in the keys.'",
+        ))
+        .get_matches();
+
+    let mut config = if let Some(conf_file) = args.value_of("config") {
+        Properties::from(std::fs::read_to_string(conf_file).unwrap())
+    } else {
+        Properties::default()
+    };
+    for key in ["mode", "peer", "listener"].iter() {
+        if let Some(value) = args.values_of(key) {
+            config.insert(key.to_string(), value.collect::<Vec<&str>>().join(","));
+        }
+    }
+    if args.is_present("no-multicast-scouting") {
+        config.insert("multicast_scouting".to_string(), "false".to_string());
+    }
+    if let Some(iface) = args.value_of("interface") {
+        // Note: the interface can be given by name or by IP address
+        let addr = match zenoh_util::net::get_interface(iface) {
+            Ok(Some(addr)) => addr,
+            Ok(None) => panic!("Invalid --interface {}: no such network interface", iface),
+            Err(e) => panic!("Invalid --interface {}: {}", iface, e),
+        };
+        config
+            .entry("multicast_interface".to_string())
+            .or_insert_with(|| iface.to_string());
+        // In peer mode, only listen on the interface (by default, zenoh listens on all interfaces)
+        if config.get("mode").map(String::as_str) != Some("client") {
+            config
+                .entry("listener".to_string())
+                .or_insert_with(|| format!("tcp/{}:0", addr));
+        }
+    }
+
+    // Without multicast scouting, the session can only connect to the configured peers,
+    // or be connected by others on the configured listeners
+    let has_endpoints = |key: &str| config.get(key).is_some_and(|v| !v.trim().is_empty());
+    if config.get("multicast_scouting").map(String::as_str) == Some("false")
+        && !has_endpoints("peer")
+        && !has_endpoints("listener")
+    {
+        panic!(
+            "No connectivity configured: multicast scouting is disabled and no peer (-e) nor listener (-l) is given"
+        );
+    }
+
+    // Note: the remaps are applied to all the topics (and action names) given as options
+    let remaps = Remaps::parse(args.values_of("remap").into_iter().flatten())
+        .unwrap_or_else(|e| panic!("{}", e));
+    // With a type hash, the keys of the Twist topics include their type and hash,
+    // and rosout is subscribed whatever its hash
+    let type_hash = args.value_of("type-hash");
+    if let Some(hash) = type_hash {
+        typehash::check(hash).unwrap_or_else(|e| panic!("{}", e));
+    }
+    let twist_type = if args.is_present("stamped") {
+        typehash::TWIST_STAMPED_TYPE
+    } else {
+        typehash::TWIST_TYPE
+    };
+    let twist_key = |topic: &str| {
+        let key = remaps.apply(topic);
+        match type_hash {
+            Some(hash) => typehash::typed_key(&key, twist_type, hash),
+            None => key,
+        }
+    };
+    let cmd_vel = twist_key(args.value_of("cmd_vel").unwrap());
+    let rosout = remaps.apply(args.value_of("rosout").unwrap());
+    let rosout = match type_hash {
+        Some(_) => typehash::typed_key(&rosout, typehash::LOG_TYPE, "*"),
+        None => rosout,
+    };
+    // Note: a negative scale would silently invert the controls, so the direction is given
+    // by an explicit flag
+    let scale = |name: &str, invert: &str| {
+        let scale: f64 = args.value_of(name).unwrap().parse().unwrap();
+        if !(scale.is_finite() && scale >= 0.0) {
+            panic!(
+                "Invalid --{} {}: the scale is a magnitude (use --{} to invert the direction)",
+                name, scale, invert
+            );
+        }
+        if args.is_present(invert) {
+            -scale
+        } else {
+            scale
+        }
+    };
+    let angular_scale = scale("angular_scale", "invert-angular");
+    let linear_scale = scale("linear_scale", "invert-linear");
+
+    let clock: Clock = args.value_of("clock").unwrap().parse().unwrap();
+
+    let debug_input = if args.is_present("debug-input") {
+        match args.value_of("debug-input") {
+            Some(file) => Some(InputTrace::File(std::fs::File::create(file).unwrap())),
+            None => Some(InputTrace::Stderr),
+        }
+    } else {
+        None
+    };
+
+    let deadzone: f64 = args.value_of("deadzone").unwrap().parse().unwrap();
+    if !(0.0..1.0).contains(&deadzone) {
+        panic!("Invalid --deadzone {}: must be in [0.0, 1.0[", deadzone);
+    }
+    if args.is_present("transform-script") && cfg!(not(feature = "script")) {
+        panic!("Invalid --transform-script: ros2-teleop is built without the 'script' feature (build with `--features script`)");
+    }
+    if args.value_of("input") == Some("spacemouse") && cfg!(not(feature = "spacemouse")) {
+        panic!("Invalid --input spacemouse: ros2-teleop is built without the 'spacemouse' feature (build with `--features spacemouse`)");
+    }
+    let joystick = if args.value_of("input") == Some("joystick") {
+        let mut centers = HashMap::new();
+        for center in args.values_of("axis-center").into_iter().flatten() {
+            match center.split_once('=') {
+                Some((axis, value)) => {
+                    centers.insert(axis.parse().unwrap(), value.parse().unwrap());
+                }
+                None => panic!("Invalid --axis-center {}: expected AXIS=VALUE", center),
+            }
+        }
+        Some(JoystickArgs {
+            device: args.value_of("joystick-device").unwrap().to_string(),
+            axis_linear: args.value_of("axis-linear").unwrap().parse().unwrap(),
+            axis_angular: args.value_of("axis-angular").unwrap().parse().unwrap(),
+            centers,
+            deadzone,
+        })
+    } else {
+        None
+    };
+
+    let log_display_hz: Option<f64> = args
+        .value_of("log-display-hz")
+        .map(|hz| hz.parse().unwrap());
+    if let Some(hz) = log_display_hz {
+        if hz <= 0.0 {
+            panic!("Invalid --log-display-hz {}: must be positive", hz);
+        }
+    }
+
+    let mut limits = Limits::default();
+    if let Some(max) = args.value_of("max-linear") {
+        limits.linear = max.parse().unwrap();
+    }
+    if let Some(max) = args.value_of("max-angular") {
+        limits.angular = max.parse().unwrap();
+    }
+    if !(limits.linear >= 0.0 && limits.angular >= 0.0) {
+        panic!("Invalid --max-linear or --max-angular: must be positive");
+    }
+
+    let mut qos = QosProfiles::default();
+    for binding in args.values_of("qos").into_iter().flatten() {
+        qos.insert(binding, twist_key)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+    let broadcast: Vec<String> = args
+        .values_of("broadcast")
+        .into_iter()
+        .flatten()
+        .map(twist_key)
+        .collect();
+    for topic in qos.topics() {
+        if *topic != cmd_vel
+            && args.value_of("follower").map(twist_key) != Some(topic.clone())
+            && !broadcast.contains(topic)
+        {
+            panic!(
+                "Invalid --qos for {}: not a target topic (--cmd_vel, --follower or --broadcast)",
+                topic
+            );
+        }
+    }
+
+    let once = args.values_of("once").map(|values| {
+        let values: Vec<f64> = values.map(|v| v.parse().unwrap()).collect();
+        let cmd = (values[0], values[1]);
+        if let Err(e) = Limits::default().check(cmd) {
+            panic!("Invalid --once: {}", e);
+        }
+        cmd
+    });
+
+    let log_backlog: usize = args.value_of("log-backlog").unwrap().parse().unwrap();
+    let log_width = args.value_of("max-log-width").map(|cols| {
+        let max: usize = cols.parse().unwrap();
+        if max < MIN_LOG_WIDTH {
+            panic!(
+                "Invalid --max-log-width {}: must be at least {}",
+                max, MIN_LOG_WIDTH
+            );
+        }
+        LogWidth {
+            max,
+            wrap: args.is_present("wrap-logs"),
+        }
+    });
+    if log_backlog == 0 {
+        panic!("Invalid --log-backlog 0: must be positive");
+    }
+
+    let rules = args.value_of("rules").map(|file| {
+        let cooldown: f64 = args.value_of("rules-cooldown").unwrap().parse().unwrap();
+        Rules::load(file, cooldown, &limits).unwrap_or_else(|e| panic!("{}", e))
+    });
+
+    let rotate_absolute = if args.is_present("rotate-absolute") {
+        Some(
+            remaps.apply(
+                args.value_of("rotate-absolute")
+                    .unwrap_or("/turtle1/rotate_absolute"),
+            ),
+        )
+    } else {
+        None
+    };
+
+    let mut triggers: Vec<Trigger> = vec![];
+    for binding in args.values_of("trigger").into_iter().flatten() {
+        let mut trigger = Trigger::parse(binding).unwrap_or_else(|e| panic!("{}", e));
+        trigger.topic = remaps.apply(&trigger.topic);
+        if triggers.iter().any(|t| t.key == trigger.key) {
+            panic!("Invalid trigger '{}': key already bound", binding);
+        }
+        if args.is_present("record-loop") && trigger.key == 'm' {
+            panic!(
+                "Invalid trigger '{}': key already bound to --record-loop",
+                binding
+            );
+        }
+        if rotate_absolute.is_some() && is_action_key(trigger.key) {
+            panic!(
+                "Invalid trigger '{}': key already bound to the rotate_absolute action",
+                binding
+            );
+        }
+        triggers.push(trigger);
+    }
+
+    let command_repeat = args.value_of("command-repeat-hz").map(|hz| {
+        let hz: f64 = hz.parse().unwrap();
+        if hz <= 0.0 {
+            panic!("Invalid --command-repeat-hz {}: must be positive", hz);
+        }
+        Duration::from_secs_f64(1.0 / hz)
+    });
+
+    let logger = if args.is_present("logger-only") {
+        let mut topics = vec![rosout.clone()];
+        topics.extend(
+            args.values_of("logger-topic")
+                .into_iter()
+                .flatten()
+                .map(|topic| remaps.apply(topic)),
+        );
+        Some(LoggerArgs {
+            topics,
+            file: args.value_of("log-file").unwrap().to_string(),
+            format: args.value_of("log-format").unwrap().parse().unwrap(),
+            level: msgs::parse_level(args.value_of("log-level").unwrap()).unwrap(),
+            rotate_size: args
+                .value_of("log-rotate-size")
+                .map(|size| size.parse().unwrap()),
+            rotate_age: args
+                .value_of("log-rotate-secs")
+                .map(|secs| Duration::from_secs(secs.parse().unwrap())),
+            rotate_keep: args.value_of("log-rotate-keep").unwrap().parse().unwrap(),
+        })
+    } else {
+        None
+    };
+
+    let timeline = args
+        .value_of("timeline-report")
+        .map(|file| Timeline::create(file, &cmd_vel).unwrap_or_else(|e| panic!("{}", e)));
+    let generate = args.value_of("generate").map(|message_type| {
+        let message_type: generate::MessageType = message_type.parse().unwrap();
+        let topic = match (args.value_of("generate-topic"), message_type) {
+            (Some(topic), _) => remaps.apply(topic),
+            (None, generate::MessageType::Twist) => cmd_vel.clone(),
+            (None, generate::MessageType::Log) => remaps.apply("/rt/rosout"),
+            (None, generate::MessageType::Pose) => remaps.apply("/rt/turtle1/pose"),
+        };
+        let hz: f64 = args.value_of("generate-hz").unwrap().parse().unwrap();
+        let period: f64 = args.value_of("generate-period").unwrap().parse().unwrap();
+        if !(hz > 0.0 && hz.is_finite() && period > 0.0 && period.is_finite()) {
+            panic!("Invalid --generate-hz or --generate-period: must be positive");
+        }
+        GenerateArgs {
+            message_type,
+            topic,
+            pattern: args.value_of("generate-pattern").unwrap().parse().unwrap(),
+            rate: Duration::from_secs_f64(1.0 / hz),
+            amplitude: args
+                .value_of("generate-amplitude")
+                .unwrap()
+                .parse()
+                .unwrap(),
+            period,
+            count: args
+                .value_of("generate-count")
+                .map(|count| count.parse().unwrap()),
+        }
+    });
+
+    Args {
+        config,
+        cmd_vel,
+        rosout,
+        linear_scale,
+        angular_scale,
+        debug_input,
+        joystick,
+        #[cfg(feature = "spacemouse")]
+        spacemouse: if args.value_of("input") == Some("spacemouse") {
+            Some((
+                args.value_of("spacemouse-device").unwrap().to_string(),
+                deadzone,
+            ))
+        } else {
+            None
+        },
+        status_lines: args.value_of("status-lines").unwrap().parse().unwrap(),
+        log_display_hz,
+        log_backlog,
+        log_width,
+        rules,
+        triggers,
+        decompress: args.value_of("decompress").unwrap().parse().unwrap(),
+        command_repeat,
+        rotate_absolute,
+        metrics_port: args
+            .value_of("metrics-port")
+            .map(|port| port.parse().unwrap()),
+        large_threshold: args.value_of("large-threshold").unwrap().parse().unwrap(),
+        allow_large: args.is_present("allow-large"),
+        follower: args.value_of("follower").map(|topic| {
+            let delay: u64 = args.value_of("follower-delay-ms").unwrap().parse().unwrap();
+            Follower::new(twist_key(topic), Duration::from_millis(delay))
+        }),
+        pick_peer: if args.is_present("pick-peer") {
+            let ms: u64 = args
+                .value_of("pick-peer")
+                .unwrap_or("1000")
+                .parse()
+                .unwrap();
+            Some(Duration::from_millis(ms))
+        } else {
+            None
+        },
+        clock,
+        rosout_version: args.value_of("rosout-version").unwrap().parse().unwrap(),
+        stamper: if args.is_present("stamped") {
+            let stamper = Stamper::new(
+                args.value_of("frame-id").unwrap().to_string(),
+                args.is_present("use-sim-time"),
+            );
+            let stamper = match args.value_of("broadcast-lead-ms") {
+                Some(ms) => stamper.with_lead(Duration::from_millis(ms.parse().unwrap())),
+                None => stamper,
+            };
+            Some(if args.is_present("header-seq") {
+                stamper.with_seq()
+            } else {
+                stamper
+            })
+        } else {
+            None
+        },
+        record_loop: args.is_present("record-loop"),
+        limits,
+        deadman: if args.is_present("deadman") {
+            let grace: u64 = args.value_of("deadman-grace-ms").unwrap().parse().unwrap();
+            let decay: u64 = args.value_of("deadman-decay-ms").unwrap().parse().unwrap();
+            Some(Deadman::new(
+                Duration::from_millis(grace),
+                Duration::from_millis(decay),
+            ))
+        } else {
+            None
+        },
+        show_keys: args.is_present("show-keys"),
+        logger,
+        once,
+        result_format: args.value_of("result-format").unwrap().parse().unwrap(),
+        qos,
+        broadcast,
+        replay_verify: args.value_of("replay-verify").map(|file| {
+            let records = record::load(file).unwrap_or_else(|e| panic!("{}", e));
+            if let Some(r) = records
+                .iter()
+                .find(|r| Limits::default().check((r.linear, r.angular)).is_err())
+            {
+                panic!("Invalid record in {}: {:?}", file, r);
+            }
+            VerifyArgs {
+                records,
+                tolerance: args.value_of("verify-tolerance").unwrap().parse().unwrap(),
+            }
+        }),
+        generate,
+        dead_reckoning: if args.is_present("dead-reckoning") {
+            let origin: Pose = args
+                .value_of("dead-reckoning")
+                .unwrap_or("0,0,0")
+                .parse()
+                .unwrap_or_else(|e| panic!("{}", e));
+            Some(DeadReckoning::new(origin))
+        } else {
+            None
+        },
+        #[cfg(feature = "script")]
+        transform_script: args
+            .value_of("transform-script")
+            .map(|file| script::TransformScript::load(file).unwrap_or_else(|e| panic!("{}", e))),
+        snapshot_dir: args.value_of("snapshot-dir").unwrap().to_string(),
+        conflict_policy: args.value_of("conflict-policy").unwrap().parse().unwrap(),
+        transform: args
+            .value_of("transform")
+            .unwrap()
+            .parse()
+            .unwrap_or_else(|e| panic!("{}", e)),
+        robot_limits: if args.is_present("robot-limits") {
+            let wait: u64 = args
+                .value_of("robot-limits-wait-ms")
+                .unwrap()
+                .parse()
+                .unwrap();
+            Some((
+                remaps.apply(
+                    args.value_of("robot-limits")
+                        .unwrap_or("/rt/turtle1/velocity_limits"),
+                ),
+                Duration::from_millis(wait),
+            ))
+        } else {
+            None
+        },
+        jitter: match command_repeat {
+            Some(period) if args.is_present("jitter-stats") => Some(Jitter::new(period)),
+            _ => None,
+        },
+        safety_override: args.value_of("override-secs").map(|secs| {
+            let secs: f64 = secs.parse().unwrap();
+            if !(secs > 0.0 && secs <= 60.0) {
+                panic!("Invalid --override-secs {}: must be in ]0, 60]", secs);
+            }
+            Override::new(Duration::from_secs_f64(secs))
+        }),
+        doctor: if args.is_present("doctor") {
+            let ms: u64 = args.value_of("doctor").unwrap_or("1000").parse().unwrap();
+            Some(Duration::from_millis(ms))
+        } else {
+            None
+        },
+        preset: args.value_of("preset").map(|name| {
+            if name.is_empty() || name.contains(|c| "*?#$".contains(c)) {
+                panic!(
+                    "Invalid --preset '{}': expected a name without wildcards",
+                    name
+                );
+            }
+            remaps.apply(&format!("{}{}", preset::PRESETS_PREFIX, name))
+        }),
+        exclusive: args.is_present("exclusive"),
+        supervisor: args
+            .value_of("enable-topic")
+            .map(|topic| Supervisor::new(remaps.apply(topic))),
+        clock_topic: if args.is_present("use-sim-time") {
+            Some(remaps.apply(args.value_of("clock-topic").unwrap()))
+        } else {
+            None
+        },
+        timeline,
+        world_frame: match args.value_of("frame") {
+            Some("world") => Some(WorldFrame::new(
+                remaps.apply(args.value_of("pose-topic").unwrap()),
+            )),
+            _ => None,
+        },
+        recorder: args
+            .value_of("record")
+            .map(|file| Recorder::create(file, clock).unwrap_or_else(|e| panic!("{}", e))),
+    }
+}
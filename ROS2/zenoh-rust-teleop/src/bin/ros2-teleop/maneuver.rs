@@ -0,0 +1,144 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use serde_derive::Deserialize;
+use std::time::{Duration, Instant};
+
+// A step of a maneuver: a command held during a duration (in seconds)
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Step {
+    pub linear: f64,
+    pub angular: f64,
+    pub duration: f64,
+}
+
+// A canned sequence of commands, played step by step (in loop if `looping`).
+pub struct Maneuver {
+    name: String,
+    steps: Vec<Step>,
+    index: usize,
+    step_start: Instant,
+    looping: bool,
+}
+
+impl Maneuver {
+    pub fn new(name: String, steps: Vec<Step>) -> Self {
+        Maneuver {
+            name,
+            steps,
+            index: 0,
+            step_start: Instant::now(),
+            looping: false,
+        }
+    }
+
+    // A maneuver restarting from its 1st step when complete, until cancelled
+    pub fn looping(name: String, steps: Vec<Step>) -> Self {
+        Maneuver {
+            looping: true,
+            ..Maneuver::new(name, steps)
+        }
+    }
+
+    // The step being played, or None if the maneuver is complete
+    pub fn current(&self) -> Option<&Step> {
+        self.steps.get(self.index)
+    }
+
+    // The time remaining before the end of the current step
+    pub fn remaining(&self) -> Duration {
+        match self.current() {
+            Some(step) => {
+                Duration::from_secs_f64(step.duration).saturating_sub(self.step_start.elapsed())
+            }
+            None => Duration::from_secs(0),
+        }
+    }
+
+    // Switch to the next step, returning it (or None if the maneuver is complete)
+    pub fn advance(&mut self) -> Option<&Step> {
+        self.index += 1;
+        if self.looping && self.index >= self.steps.len() {
+            self.index = 0;
+        }
+        self.step_start = Instant::now();
+        self.current()
+    }
+
+    pub fn progress(&self) -> String {
+        format!(
+            "{}{} (step {}/{})",
+            if self.looping { "LOOPING " } else { "" },
+            self.name,
+            (self.index + 1).min(self.steps.len()),
+            self.steps.len()
+        )
+    }
+}
+
+// The recording of the published commands as the steps of a maneuver (`--record-loop`)
+pub struct Recording {
+    steps: Vec<Step>,
+    last: Option<(Instant, (f64, f64))>,
+}
+
+impl Recording {
+    pub fn start() -> Self {
+        Recording {
+            steps: vec![],
+            last: None,
+        }
+    }
+
+    pub fn record(&mut self, cmd: (f64, f64)) {
+        let now = Instant::now();
+        match self.last {
+            // Note: a repeated command extends the current step
+            Some((_, last_cmd)) if last_cmd == cmd => (),
+            Some((start, (linear, angular))) => {
+                self.steps.push(Step {
+                    linear,
+                    angular,
+                    duration: now.duration_since(start).as_secs_f64(),
+                });
+                self.last = Some((now, cmd));
+            }
+            None => self.last = Some((now, cmd)),
+        }
+    }
+
+    // The number of recorded steps (including the one in progress)
+    pub fn len(&self) -> usize {
+        self.steps.len() + self.last.map_or(0, |_| 1)
+    }
+
+    // The recorded steps, the last one ending now
+    pub fn stop(mut self) -> Vec<Step> {
+        if let Some((start, (linear, angular))) = self.last.take() {
+            self.steps.push(Step {
+                linear,
+                angular,
+                duration: start.elapsed().as_secs_f64(),
+            });
+        }
+        self.steps
+    }
+}
+
+// Wait for the end of the current step of an optional maneuver (forever if there is no maneuver)
+pub async fn step_end(maneuver: &Option<Maneuver>) {
+    match maneuver {
+        Some(maneuver) => async_std::task::sleep(maneuver.remaining()).await,
+        None => futures::future::pending().await,
+    }
+}
@@ -0,0 +1,135 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::net::{TcpListener, TcpStream};
+use async_std::sync::Arc;
+use futures::prelude::*;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// A counter or a gauge
+#[derive(Default)]
+pub struct Metric(AtomicU64);
+
+impl Metric {
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// The metrics updated by the events loop, exposed by `--metrics-port`
+#[derive(Default)]
+pub struct Metrics {
+    pub commands_sent: Metric,
+    pub logs_received: Metric,
+    pub decode_errors: Metric,
+    pub publish_errors: Metric,
+    pub peers: Metric,
+    pub routers: Metric,
+}
+
+impl Metrics {
+    // The metrics in Prometheus text format
+    // (see https://prometheus.io/docs/instrumenting/exposition_formats/)
+    fn render(&self) -> String {
+        let metrics = [
+            (
+                "commands_sent_total",
+                "counter",
+                "Twist commands published.",
+                &self.commands_sent,
+            ),
+            (
+                "logs_received_total",
+                "counter",
+                "Log messages received and decoded.",
+                &self.logs_received,
+            ),
+            (
+                "decode_errors_total",
+                "counter",
+                "Received messages that failed to decode.",
+                &self.decode_errors,
+            ),
+            (
+                "publish_errors_total",
+                "counter",
+                "Failed publications.",
+                &self.publish_errors,
+            ),
+            ("peers", "gauge", "Connected zenoh peers.", &self.peers),
+            (
+                "routers",
+                "gauge",
+                "Connected zenoh routers.",
+                &self.routers,
+            ),
+        ];
+        let mut text = String::new();
+        for (name, kind, help, value) in metrics.iter() {
+            let _ = writeln!(text, "# HELP ros2_teleop_{} {}", name, help);
+            let _ = writeln!(text, "# TYPE ros2_teleop_{} {}", name, kind);
+            let _ = writeln!(text, "ros2_teleop_{} {}", name, value.get());
+        }
+        text
+    }
+}
+
+// Serve the metrics over HTTP on "/metrics", to be scraped by Prometheus
+pub async fn serve(listener: TcpListener, metrics: Arc<Metrics>) {
+    let mut incoming = listener.incoming();
+    while let Some(stream) = incoming.next().await {
+        match stream {
+            Ok(stream) => {
+                async_std::task::spawn(handle(stream, metrics.clone()));
+            }
+            Err(e) => log::warn!("Metrics connection error: {}", e),
+        }
+    }
+}
+
+async fn handle(mut stream: TcpStream, metrics: Arc<Metrics>) {
+    // Only the request line matters: the rest of the request is ignored
+    let mut buf = [0u8; 1024];
+    let len = match stream.read(&mut buf).await {
+        Ok(len) => len,
+        Err(e) => {
+            log::warn!("Metrics request error: {}", e);
+            return;
+        }
+    };
+    let request = String::from_utf8_lossy(&buf[..len]);
+    let mut request_line = request.lines().next().unwrap_or("").split(' ');
+    let response = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            let body = metrics.render();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        }
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+    };
+    if let Err(e) = stream.write_all(response.as_bytes()).await {
+        log::warn!("Metrics response error: {}", e);
+    }
+}
@@ -0,0 +1,345 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use serde::de::{Deserializer, Visitor};
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct Twist {
+    pub linear: Vector3,
+    pub angular: Vector3,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub struct Time {
+    pub sec: i32,
+    pub nanosec: u32,
+}
+
+// std_msgs/Header
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct Header {
+    pub stamp: Time,
+    pub frame_id: String,
+}
+
+// geometry_msgs/TwistStamped
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct TwistStamped {
+    pub header: Header,
+    pub twist: Twist,
+}
+
+// std_msgs/Header as in ROS1, starting with a sequence number (removed in ROS2)
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct SeqHeader {
+    pub seq: u32,
+    pub stamp: Time,
+    pub frame_id: String,
+}
+
+// geometry_msgs/TwistStamped with the ROS1 header
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct SeqTwistStamped {
+    pub header: SeqHeader,
+    pub twist: Twist,
+}
+
+// rosgraph_msgs/Clock
+#[derive(Deserialize, PartialEq)]
+pub struct ClockMsg {
+    pub clock: Time,
+}
+
+// Note: the strings are decoded lossily, as some publishers send invalid UTF-8 or embedded nulls.
+// Rather than dropping the whole Log, the invalid characters are replaced with U+FFFD.
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct Log {
+    pub stamp: Time,
+    pub level: u8,
+    #[serde(deserialize_with = "lossy_string")]
+    pub name: String,
+    #[serde(deserialize_with = "lossy_string")]
+    pub msg: String,
+    #[serde(deserialize_with = "lossy_string")]
+    pub file: String,
+    #[serde(deserialize_with = "lossy_string")]
+    pub function: String,
+    pub line: u32,
+}
+
+// Deserialize a CDR string (length + bytes + terminating null) without failing on invalid UTF-8
+fn lossy_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    struct LossyStringVisitor;
+
+    impl<'de> Visitor<'de> for LossyStringVisitor {
+        type Value = String;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a string")
+        }
+
+        fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<String, E> {
+            // remove the terminating null character(s)
+            let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+            Ok(String::from_utf8_lossy(&bytes[..len]).replace('\0', "\u{FFFD}"))
+        }
+
+        fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<String, E> {
+            self.visit_bytes(s.as_bytes())
+        }
+    }
+
+    // Note: the CDR deserializer reads bytes with the same layout than a string
+    deserializer.deserialize_byte_buf(LossyStringVisitor)
+}
+
+impl Log {
+    // Whether the fields look sensible, a nonsensical value hinting at a layout mismatch
+    pub fn is_plausible(&self) -> bool {
+        [10, 20, 30, 40, 50].contains(&self.level) && self.stamp.nanosec < 1_000_000_000
+    }
+
+    // The stamp and name preceding the message in the displayed line
+    pub fn prefix(&self) -> String {
+        format!(
+            "[{}.{}] [{}]: ",
+            self.stamp.sec, self.stamp.nanosec, self.name
+        )
+    }
+
+    pub fn level_name(&self) -> &'static str {
+        match self.level {
+            10 => "DEBUG",
+            20 => "INFO",
+            30 => "WARN",
+            40 => "ERROR",
+            50 => "FATAL",
+            _ => "UNKNOWN",
+        }
+    }
+}
+
+// Decode a rosout message (rcl_interfaces/msg/Log)
+pub fn decode_log<R: Read>(reader: R, size_limit: u64) -> Result<Log, String> {
+    cdr::deserialize_from::<_, Log, _>(reader, cdr::size::Bounded(size_limit))
+        .map_err(|e| format!("Error decoding Log: {}", e))
+}
+
+// The ROS2 distro of the robot, selecting the Log layout of the rosout messages (`--rosout-version`)
+// Note: the distros since Foxy share the same layout, so they're all decoded by decode_log
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RosoutVersion {
+    Foxy,
+    Galactic,
+    Humble,
+    Iron,
+    Jazzy,
+}
+
+impl FromStr for RosoutVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "foxy" => Ok(RosoutVersion::Foxy),
+            "galactic" => Ok(RosoutVersion::Galactic),
+            "humble" => Ok(RosoutVersion::Humble),
+            "iron" => Ok(RosoutVersion::Iron),
+            "jazzy" => Ok(RosoutVersion::Jazzy),
+            _ => Err(format!("Unknown rosout version: {}", s)),
+        }
+    }
+}
+
+impl RosoutVersion {
+    pub fn decode<R: Read>(&self, reader: R, size_limit: u64) -> Result<Log, String> {
+        match self {
+            RosoutVersion::Foxy
+            | RosoutVersion::Galactic
+            | RosoutVersion::Humble
+            | RosoutVersion::Iron
+            | RosoutVersion::Jazzy => decode_log(reader, size_limit),
+        }
+    }
+}
+
+// The severity level of a Log, by name
+pub fn parse_level(name: &str) -> Result<u8, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "debug" => Ok(10),
+        "info" => Ok(20),
+        "warn" => Ok(30),
+        "error" => Ok(40),
+        "fatal" => Ok(50),
+        _ => Err(format!("Unknown log level: {}", name)),
+    }
+}
+
+impl fmt::Display for Log {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.prefix(), self.msg)
+    }
+}
+
+// turtlesim/Pose
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct Pose {
+    pub x: f32,
+    pub y: f32,
+    pub theta: f32,
+    pub linear_velocity: f32,
+    pub angular_velocity: f32,
+}
+
+// std_msgs/Bool
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct Bool {
+    pub data: bool,
+}
+
+// std_msgs/Empty
+// Note: as an empty struct is not valid in IDL, ROS2 generates a dummy u8 member.
+#[derive(Serialize, PartialEq)]
+pub struct Empty {
+    pub structure_needs_at_least_one_member: u8,
+}
+
+// std_msgs/String
+#[derive(Serialize, PartialEq)]
+pub struct StringMsg {
+    pub data: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdr::Infinite;
+
+    // A CDR little-endian Log, with the strings given as raw bytes (length prefix included)
+    fn encode_log(name: &[u8], msg: &[u8], file: &[u8], function: &[u8]) -> Vec<u8> {
+        let mut buf = vec![0x00, 0x01, 0x00, 0x00];
+        buf.extend_from_slice(&12i32.to_le_bytes());
+        buf.extend_from_slice(&345u32.to_le_bytes());
+        buf.push(40);
+        for s in [name, msg, file, function] {
+            // the strings' lengths are aligned on 4 bytes (after the 4-byte encapsulation header)
+            while (buf.len() - 4) % 4 != 0 {
+                buf.push(0);
+            }
+            buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            buf.extend_from_slice(s);
+        }
+        while (buf.len() - 4) % 4 != 0 {
+            buf.push(0);
+        }
+        buf.extend_from_slice(&42u32.to_le_bytes());
+        buf
+    }
+
+    fn decode(buf: &[u8]) -> Result<Log, cdr::Error> {
+        cdr::deserialize_from::<_, Log, _>(buf, Infinite)
+    }
+
+    #[test]
+    fn same_layout_for_all_distros() {
+        let buf = encode_log(b"node\0", b"hello\0", b"main.c\0", b"run\0");
+        for distro in ["foxy", "galactic", "humble", "iron", "jazzy"] {
+            let version: RosoutVersion = distro.parse().unwrap();
+            let log = version.decode(&buf[..], 1024).unwrap();
+            assert_eq!(log.msg, "hello");
+            assert_eq!(log.line, 42);
+        }
+        assert!("rolling".parse::<RosoutVersion>().is_err());
+    }
+
+    #[test]
+    fn valid_log() {
+        let log = decode(&encode_log(b"node\0", b"hello\0", b"main.c\0", b"run\0")).unwrap();
+        assert_eq!(
+            log.stamp,
+            Time {
+                sec: 12,
+                nanosec: 345
+            }
+        );
+        assert_eq!(log.level, 40);
+        assert_eq!(log.name, "node");
+        assert_eq!(log.msg, "hello");
+        assert_eq!(log.file, "main.c");
+        assert_eq!(log.function, "run");
+        assert_eq!(log.line, 42);
+    }
+
+    #[test]
+    fn invalid_utf8_in_msg() {
+        let log = decode(&encode_log(
+            b"node\0",
+            b"bad \xff\xfe end\0",
+            b"main.c\0",
+            b"run\0",
+        ))
+        .unwrap();
+        assert_eq!(log.msg, "bad \u{FFFD}\u{FFFD} end");
+        assert_eq!(log.name, "node");
+        assert_eq!(log.file, "main.c");
+        assert_eq!(log.function, "run");
+        assert_eq!(log.line, 42);
+    }
+
+    #[test]
+    fn embedded_nul_in_file() {
+        let log = decode(&encode_log(b"node\0", b"hello\0", b"main\0.c\0", b"run\0")).unwrap();
+        assert_eq!(log.file, "main\u{FFFD}.c");
+        assert_eq!(log.msg, "hello");
+        assert_eq!(log.function, "run");
+        assert_eq!(log.line, 42);
+    }
+
+    #[test]
+    fn missing_terminator() {
+        let log = decode(&encode_log(b"node\0", b"hello", b"main.c\0", b"run\0")).unwrap();
+        assert_eq!(log.msg, "hello");
+        assert_eq!(log.name, "node");
+        assert_eq!(log.file, "main.c");
+        assert_eq!(log.function, "run");
+        assert_eq!(log.line, 42);
+    }
+
+    #[test]
+    fn truncated_length_prefix() {
+        let buf = encode_log(b"node\0", b"hello\0", b"main.c\0", b"run\0");
+        // cut in the middle of the function's length prefix
+        let end = buf.len() - 4 - 4 - 2;
+        assert!(decode(&buf[..end]).is_err());
+    }
+
+    #[test]
+    fn length_beyond_payload() {
+        let mut buf = encode_log(b"node\0", b"hello\0", b"main.c\0", b"run\0");
+        // the msg's length claims more bytes than the payload: an error rather than a panic
+        buf[28..32].copy_from_slice(&4096u32.to_le_bytes());
+        assert!(decode(&buf).is_err());
+        assert!(cdr::deserialize_from::<_, Log, _>(&buf[..], cdr::size::Bounded(1024)).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use zenoh::net::info::{ZN_INFO_PEER_PID_KEY, ZN_INFO_ROUTER_PID_KEY};
+use zenoh::net::Session;
+
+// The period of the checks of the connection before publishing
+const CONNECTION_POLL: Duration = Duration::from_millis(50);
+
+// The format of the result printed by the `--once` mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResultFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for ResultFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(ResultFormat::Text),
+            "json" => Ok(ResultFormat::Json),
+            _ => Err(format!("Unknown result format: {}", s)),
+        }
+    }
+}
+
+// Print the result of the publication of a single command, as the last line of the output,
+// for the calling scripts. `result` is the published payload size, or the publication error.
+// Note: as zenoh 0.5 doesn't report the delivery of a publication, the command is only known
+// to be sent out when a peer or router is connected: otherwise it's reported as failed.
+pub fn print_result(
+    format: ResultFormat,
+    key: &str,
+    (linear, angular): (f64, f64),
+    (peers, routers): (usize, usize),
+    result: &Result<usize, String>,
+) -> bool {
+    let result = match result {
+        Ok(_) if peers + routers == 0 => Err("no peer or router connected".to_string()),
+        result => result.clone(),
+    };
+    match format {
+        ResultFormat::Text => match &result {
+            Ok(bytes) => println!(
+                "Published linear: {}, angular: {} to {} ({} bytes)",
+                linear, angular, key, bytes
+            ),
+            Err(e) => println!(
+                "Failed to publish linear: {}, angular: {} to {}: {}",
+                linear, angular, key, e
+            ),
+        },
+        // Note: printed as a single line, even on error, so it can always be parsed
+        ResultFormat::Json => println!(
+            "{}",
+            serde_json::json!({
+                "key": key,
+                "linear": linear,
+                "angular": angular,
+                "bytes": result.as_ref().ok(),
+                "peers": peers,
+                "routers": routers,
+                "success": result.is_ok(),
+                "error": result.as_ref().err(),
+            })
+        ),
+    }
+    result.is_ok()
+}
+
+// Wait until a peer or router is connected (or the timeout), returning their numbers
+pub async fn wait_connection(session: &Session, timeout: Duration) -> (usize, usize) {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let info = session.info().await;
+        let connected = (
+            crate::count_pids(info.get(&ZN_INFO_PEER_PID_KEY)),
+            crate::count_pids(info.get(&ZN_INFO_ROUTER_PID_KEY)),
+        );
+        if connected.0 + connected.1 > 0 || Instant::now() >= deadline {
+            return connected;
+        }
+        async_std::task::sleep(CONNECTION_POLL).await;
+    }
+}
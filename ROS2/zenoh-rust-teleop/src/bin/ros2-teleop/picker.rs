@@ -0,0 +1,95 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use futures::prelude::*;
+use std::io::BufRead;
+use std::time::Duration;
+use zenoh::net::*;
+use zenoh::Properties;
+
+// A peer or router discovered by scouting
+struct Endpoint {
+    pid: String,
+    whatami: String,
+    locators: Vec<String>,
+}
+
+// Scout for the zenoh peers and routers during `duration`, and let the operator pick the one to connect to.
+// Returns the locators of the chosen endpoint, or an error if none was discovered.
+// Note: this must be called before the terminal is switched to raw mode (the choice is read as a line).
+pub async fn pick_peer(config: &Properties, duration: Duration) -> Result<Vec<String>, String> {
+    println!("Scouting for {} ms...", duration.as_millis());
+    let mut receiver = scout(whatami::PEER | whatami::ROUTER, config.clone().into())
+        .await
+        .map_err(|e| format!("Failed to scout: {}", e))?;
+    let mut endpoints: Vec<Endpoint> = vec![];
+    let collect = async {
+        while let Some(hello) = receiver.next().await {
+            let pid = hello.pid.map(|pid| pid.to_string()).unwrap_or_default();
+            let locators: Vec<String> = hello
+                .locators
+                .unwrap_or_default()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            // Note: the same endpoint replies to each scout message
+            if locators.is_empty() || endpoints.iter().any(|e| e.pid == pid) {
+                continue;
+            }
+            endpoints.push(Endpoint {
+                pid,
+                whatami: whatami::to_string(hello.whatami.unwrap_or(whatami::ROUTER)),
+                locators,
+            });
+        }
+    };
+    let _ = async_std::future::timeout(duration, collect).await;
+    drop(receiver);
+
+    match endpoints.len() {
+        0 => Err("No zenoh peer or router discovered".to_string()),
+        1 => {
+            let endpoint = endpoints.remove(0);
+            println!(
+                "Discovered a single {} ({}): connecting to it",
+                endpoint.whatami, endpoint.pid
+            );
+            Ok(endpoint.locators)
+        }
+        n => {
+            for (i, endpoint) in endpoints.iter().enumerate() {
+                println!(
+                    "  [{}] {} {} {}",
+                    i + 1,
+                    endpoint.whatami,
+                    endpoint.pid,
+                    endpoint.locators.join(",")
+                );
+            }
+            let stdin = std::io::stdin();
+            loop {
+                print!("Connect to [1-{}]: ", n);
+                let _ = std::io::Write::flush(&mut std::io::stdout());
+                let mut line = String::new();
+                match stdin.lock().read_line(&mut line) {
+                    Ok(0) | Err(_) => return Err("No endpoint chosen".to_string()),
+                    Ok(_) => (),
+                }
+                match line.trim().parse::<usize>() {
+                    Ok(i) if (1..=n).contains(&i) => return Ok(endpoints.remove(i - 1).locators),
+                    _ => println!("Invalid choice: {}", line.trim()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::future::timeout;
+use futures::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+use zenoh::net::*;
+
+use crate::limits::Limits;
+
+// The prefix of the keys of the shared presets (`--preset NAME`)
+pub const PRESETS_PREFIX: &str = "/teleop/presets/";
+// The maximum time to wait for the stored preset
+const GET_TIMEOUT: Duration = Duration::from_secs(1);
+
+// The working scales and limits of an operator, shared with the other teleops as JSON
+// (e.g. {"linear_scale":1.0,"angular_scale":0.5,"max_linear":1.5,"max_angular":null}).
+// The scales are magnitudes (the inversions are local), and a null limit is unlimited.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Preset {
+    pub linear_scale: f64,
+    pub angular_scale: f64,
+    pub max_linear: Option<f64>,
+    pub max_angular: Option<f64>,
+}
+
+impl Preset {
+    pub fn new((linear_scale, angular_scale): (f64, f64), limits: &Limits) -> Self {
+        let finite = |limit: f64| Some(limit).filter(|l| l.is_finite());
+        Preset {
+            linear_scale: linear_scale.abs(),
+            angular_scale: angular_scale.abs(),
+            max_linear: finite(limits.linear),
+            max_angular: finite(limits.angular),
+        }
+    }
+
+    pub fn limits(&self) -> Limits {
+        Limits {
+            linear: self.max_linear.unwrap_or(f64::INFINITY),
+            angular: self.max_angular.unwrap_or(f64::INFINITY),
+        }
+    }
+
+    // Reject the negative or non-finite values of a received preset
+    fn check(&self) -> Result<(), String> {
+        let values = [
+            Some(self.linear_scale),
+            Some(self.angular_scale),
+            self.max_linear,
+            self.max_angular,
+        ];
+        if values.iter().flatten().all(|v| v.is_finite() && *v >= 0.0) {
+            Ok(())
+        } else {
+            Err(format!("invalid preset: {}", self))
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let limit = |limit: Option<f64>| limit.map_or("none".to_string(), |l| l.to_string());
+        write!(
+            f,
+            "scales {}, {} | limits {}, {}",
+            self.linear_scale,
+            self.angular_scale,
+            limit(self.max_linear),
+            limit(self.max_angular)
+        )
+    }
+}
+
+// Put the preset on its key, to be stored by a zenoh storage
+pub async fn put(session: &Session, key: &str, preset: &Preset) -> Result<(), String> {
+    let json = serde_json::to_vec(preset).unwrap();
+    session
+        .write_ext(
+            &key.into(),
+            json.into(),
+            encoding::APP_JSON,
+            data_kind::PUT,
+            CongestionControl::Block,
+        )
+        .await
+        .map_err(|e| format!("failed to put the preset on {}: {}", key, e))
+}
+
+// Get the preset stored on its key
+pub async fn get(session: &Session, key: &str) -> Result<Preset, String> {
+    let mut replies = session
+        .query(
+            &key.into(),
+            "",
+            QueryTarget::default(),
+            QueryConsolidation::default(),
+        )
+        .await
+        .map_err(|e| format!("failed to query {}: {}", key, e))?;
+    let reply = match timeout(GET_TIMEOUT, replies.next()).await {
+        Ok(Some(reply)) => reply,
+        _ => {
+            return Err(format!(
+                "no preset stored on {} (no reply in {} ms)",
+                key,
+                GET_TIMEOUT.as_millis()
+            ))
+        }
+    };
+    let preset: Preset = serde_json::from_slice(&reply.data.payload.contiguous())
+        .map_err(|e| format!("failed to decode the preset on {}: {}", key, e))?;
+    preset.check()?;
+    Ok(preset)
+}
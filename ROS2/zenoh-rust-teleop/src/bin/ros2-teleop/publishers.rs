@@ -0,0 +1,162 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::future::timeout;
+use futures::prelude::*;
+use std::time::{Duration, Instant};
+use zenoh::net::*;
+
+// The prefix of the keys announcing the teleops publishing on a cmd_vel key
+const PUBLISHERS_PREFIX: &str = "/teleop/publishers";
+// The maximum time to wait for the replies of the other teleops
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(1);
+// The period of the announces (queries) of a running teleop
+const ANNOUNCE_PERIOD: Duration = Duration::from_secs(3);
+// The time after which a teleop not announced anymore (e.g. exited) is dropped
+const EXPIRY: Duration = Duration::from_secs(10);
+
+// The discovery of the other teleops publishing on the same cmd_vel key.
+//
+// As zenoh 0.5 doesn't report the publishers of a key, each teleop declares a queryable on
+// PREFIX/<cmd_vel>/<session id>, and queries the others at startup. The query carries the
+// session id of the querying teleop, so the teleops already running also detect it. Then each
+// teleop re-announces itself with a query every ANNOUNCE_PERIOD, and drops the teleops not
+// announced within EXPIRY.
+// Note: the other publishers (e.g. a ROS2 node through the bridge) are not detected.
+pub struct Publishers {
+    cmd_vel: String,
+    pub pid: String,
+    // the session ids of the other teleops detected, and when they were last announced
+    others: Vec<(String, Instant)>,
+    last_announce: Instant,
+}
+
+impl Publishers {
+    pub fn new(cmd_vel: &str, pid: String) -> Self {
+        Publishers {
+            cmd_vel: cmd_vel.to_string(),
+            pid,
+            others: vec![],
+            last_announce: Instant::now(),
+        }
+    }
+
+    // The key of the queryable announcing this teleop
+    pub fn announce_key(&self) -> String {
+        format!("{}{}/{}", PUBLISHERS_PREFIX, self.cmd_vel, self.pid)
+    }
+
+    pub fn others(&self) -> Vec<String> {
+        self.others.iter().map(|(pid, _)| pid.clone()).collect()
+    }
+
+    // Query the teleops announced on the same cmd_vel key, returning their session ids.
+    // Note: to be called before declaring the queryable of this teleop, which would only reply
+    // once the event loop runs (i.e. after DISCOVERY_TIMEOUT)
+    pub async fn discover(&mut self, session: &Session) -> Result<Vec<String>, String> {
+        let mut replies = self.query(session).await?;
+        let replies = async {
+            while let Some(reply) = replies.next().await {
+                let pid = String::from_utf8_lossy(&reply.data.payload.contiguous()).to_string();
+                self.add(pid, Instant::now());
+            }
+        };
+        // Note: the replies stream ends when all the queryables replied
+        let _ = timeout(DISCOVERY_TIMEOUT, replies).await;
+        self.last_announce = Instant::now();
+        Ok(self.others())
+    }
+
+    // Re-announce this teleop to the others if ANNOUNCE_PERIOD elapsed, ignoring the replies
+    // (the others announce themselves too)
+    pub async fn announce(&mut self, session: &Session, now: Instant) -> Result<(), String> {
+        if now.duration_since(self.last_announce) < ANNOUNCE_PERIOD {
+            return Ok(());
+        }
+        self.last_announce = now;
+        self.query(session).await.map(|_| ())
+    }
+
+    // Drop the teleops not announced within EXPIRY, returning their session ids
+    pub fn expire(&mut self, now: Instant) -> Vec<String> {
+        let (expired, others) = self
+            .others
+            .drain(..)
+            .partition(|&(_, seen)| now.duration_since(seen) >= EXPIRY);
+        self.others = others;
+        expired.into_iter().map(|(pid, _)| pid).collect()
+    }
+
+    async fn query(&self, session: &Session) -> Result<ReplyReceiver, String> {
+        let selector = format!("{}{}/*", PUBLISHERS_PREFIX, self.cmd_vel);
+        session
+            .query(
+                &selector.as_str().into(),
+                &format!("pid={}", self.pid),
+                QueryTarget {
+                    kind: queryable::EVAL,
+                    target: Target::All,
+                },
+                QueryConsolidation::none(),
+            )
+            .await
+            .map_err(|e| format!("failed to query {}: {}", selector, e))
+    }
+
+    // Reply to the query of another teleop, returning its session id if it's a new one
+    pub fn on_query(&mut self, query: Query, now: Instant) -> Option<String> {
+        query.reply(Sample {
+            res_name: self.announce_key(),
+            payload: self.pid.as_bytes().to_vec().into(),
+            data_info: None,
+        });
+        let pid = query.predicate.strip_prefix("pid=")?.to_string();
+        if self.add(pid.clone(), now) {
+            Some(pid)
+        } else {
+            None
+        }
+    }
+
+    // Add or refresh a teleop, returning true if it's a new one
+    fn add(&mut self, pid: String, now: Instant) -> bool {
+        if pid == self.pid {
+            return false;
+        }
+        if let Some((_, seen)) = self.others.iter_mut().find(|(p, _)| *p == pid) {
+            *seen = now;
+            return false;
+        }
+        self.others.push((pid, now));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_the_teleops_not_announced() {
+        let mut publishers = Publishers::new("/rt/cmd_vel", "A".to_string());
+        let start = Instant::now();
+        assert!(!publishers.add("A".to_string(), start));
+        assert!(publishers.add("B".to_string(), start));
+        assert!(publishers.add("C".to_string(), start));
+        // an announce refreshes a known teleop
+        assert!(!publishers.add("B".to_string(), start + EXPIRY / 2));
+        assert!(publishers.expire(start + EXPIRY / 2).is_empty());
+        assert_eq!(publishers.expire(start + EXPIRY), vec!["C".to_string()]);
+        assert_eq!(publishers.others(), vec!["B".to_string()]);
+    }
+}
@@ -0,0 +1,116 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::collections::HashMap;
+use std::fmt;
+use zenoh::net::CongestionControl;
+
+// The QoS options of the later zenoh versions
+const UNSUPPORTED: [&str; 3] = ["reliability", "priority", "express"];
+
+// The QoS options of the publications to a target topic
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QosProfile {
+    pub congestion_control: CongestionControl,
+}
+
+impl Default for QosProfile {
+    fn default() -> Self {
+        QosProfile {
+            congestion_control: CongestionControl::Drop,
+        }
+    }
+}
+
+impl QosProfile {
+    // The profile with the other congestion control (toggled at runtime with CTRL+B)
+    pub fn toggled(self) -> Self {
+        QosProfile {
+            congestion_control: match self.congestion_control {
+                CongestionControl::Block => CongestionControl::Drop,
+                CongestionControl::Drop => CongestionControl::Block,
+            },
+        }
+    }
+
+    // Parse a comma-separated list of options, as "congestion:block"
+    fn parse(options: &str) -> Result<Self, String> {
+        let mut profile = QosProfile::default();
+        for option in options.split(',') {
+            match option.split_once(':') {
+                Some(("congestion", "block")) => {
+                    profile.congestion_control = CongestionControl::Block
+                }
+                Some(("congestion", "drop")) => {
+                    profile.congestion_control = CongestionControl::Drop
+                }
+                // Note: zenoh 0.5 always publishes as reliable, with the default priority and without express option
+                _ if UNSUPPORTED.contains(&option.split(':').next().unwrap_or_default()) => {
+                    return Err(format!(
+                        "QoS option {} not supported by zenoh 0.5 publications",
+                        option
+                    ))
+                }
+                _ => {
+                    return Err(format!(
+                        "Unknown QoS option {}: expected congestion:block or congestion:drop",
+                        option
+                    ))
+                }
+            }
+        }
+        Ok(profile)
+    }
+}
+
+impl fmt::Display for QosProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.congestion_control {
+            CongestionControl::Block => f.write_str("congestion:block"),
+            CongestionControl::Drop => f.write_str("congestion:drop"),
+        }
+    }
+}
+
+// The QoS profiles of the target topics (`--qos TOPIC=OPTIONS`), the others using the default profile
+#[derive(Default)]
+pub struct QosProfiles {
+    profiles: HashMap<String, QosProfile>,
+}
+
+impl QosProfiles {
+    pub fn insert(&mut self, binding: &str, remap: impl Fn(&str) -> String) -> Result<(), String> {
+        match binding.split_once('=') {
+            Some((topic, options)) if !topic.is_empty() => {
+                let profile = QosProfile::parse(options)
+                    .map_err(|e| format!("Invalid --qos {}: {}", binding, e))?;
+                if self.profiles.insert(remap(topic), profile).is_some() {
+                    return Err(format!(
+                        "Invalid --qos {}: {} already has a profile",
+                        binding, topic
+                    ));
+                }
+                Ok(())
+            }
+            _ => Err(format!("Invalid --qos {}: expected TOPIC=OPTIONS", binding)),
+        }
+    }
+
+    pub fn get(&self, topic: &str) -> QosProfile {
+        self.profiles.get(topic).copied().unwrap_or_default()
+    }
+
+    pub fn topics(&self) -> impl Iterator<Item = &String> {
+        self.profiles.keys()
+    }
+}
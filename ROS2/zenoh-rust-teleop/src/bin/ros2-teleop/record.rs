@@ -0,0 +1,81 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use serde_derive::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+use crate::clock::Clock;
+
+// A published command, as recorded in the `--record` file (1 JSON object per line), e.g.:
+//   {"time":12.345,"linear":2.0,"angular":0.0}
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Record {
+    pub time: f64,
+    pub linear: f64,
+    pub angular: f64,
+}
+
+// Load the commands of a record file
+pub fn load(path: &str) -> Result<Vec<Record>, String> {
+    let file =
+        File::open(path).map_err(|e| format!("Failed to open record file {}: {}", path, e))?;
+    let mut records = vec![];
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read record file {}: {}", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Record = serde_json::from_str(&line)
+            .map_err(|e| format!("Invalid record at {}:{}: {}", path, i + 1, e))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// The recorder of the published commands, timestamped with the `--clock`
+pub struct Recorder {
+    file: BufWriter<File>,
+    clock: Clock,
+}
+
+impl Recorder {
+    pub fn create(path: &str, clock: Clock) -> Result<Recorder, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create record file {}: {}", path, e))?;
+        Ok(Recorder {
+            file: BufWriter::new(file),
+            clock,
+        })
+    }
+
+    pub fn record(&mut self, linear: f64, angular: f64) {
+        let record = Record {
+            time: self.clock.now(),
+            linear,
+            angular,
+        };
+        let res = serde_json::to_string(&record)
+            .map_err(|e| e.to_string())
+            .and_then(|line| writeln!(self.file, "{}", line).map_err(|e| e.to_string()));
+        if let Err(e) = res {
+            log::warn!("Failed to record command: {}", e);
+        }
+    }
+
+    pub fn close(mut self) {
+        if let Err(e) = self.file.flush() {
+            log::warn!("Failed to flush record file: {}", e);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::collections::HashMap;
+
+// The prefixes of the zenoh keys of the ROS2 topics, as routed by the zenoh/DDS bridge
+const ROS_PREFIXES: [&str; 3] = ["/rt", "/rq", "/rr"];
+
+// The remapping table of the topic names (`--remap FROM:=TO`), as with the ROS2 CLI.
+//
+// FROM and TO are either ROS2 names (e.g. "/turtle1/cmd_vel"), remapping the corresponding
+// zenoh keys (e.g. "/rt/turtle1/cmd_vel"), or zenoh keys.
+#[derive(Default)]
+pub struct Remaps {
+    remaps: HashMap<String, String>,
+}
+
+impl Remaps {
+    pub fn parse<'a, I: IntoIterator<Item = &'a str>>(remaps: I) -> Result<Remaps, String> {
+        let mut map = HashMap::new();
+        for remap in remaps {
+            match remap.split_once(":=") {
+                Some((from, to)) if !from.is_empty() && !to.is_empty() => {
+                    if map.insert(from.to_string(), to.to_string()).is_some() {
+                        return Err(format!(
+                            "Invalid --remap {}: {} already remapped",
+                            remap, from
+                        ));
+                    }
+                }
+                _ => return Err(format!("Invalid --remap {}: expected FROM:=TO", remap)),
+            }
+        }
+        Ok(Remaps { remaps: map })
+    }
+
+    // The remapped zenoh key or ROS2 name
+    pub fn apply(&self, name: &str) -> String {
+        if let Some(to) = self.remaps.get(name) {
+            return to.clone();
+        }
+        for prefix in ROS_PREFIXES.iter() {
+            if let Some(to) = name
+                .strip_prefix(prefix)
+                .and_then(|ros_name| self.remaps.get(ros_name))
+            {
+                return format!("{}{}", prefix, to);
+            }
+        }
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaps_the_ros2_names_with_their_prefix() {
+        let remaps = Remaps::parse(vec!["/turtle1/cmd_vel:=/robot1/cmd_vel"]).unwrap();
+        assert_eq!(remaps.apply("/rt/turtle1/cmd_vel"), "/rt/robot1/cmd_vel");
+        assert_eq!(remaps.apply("/rq/turtle1/cmd_vel"), "/rq/robot1/cmd_vel");
+        assert_eq!(remaps.apply("/turtle1/cmd_vel"), "/robot1/cmd_vel");
+        assert_eq!(remaps.apply("/rt/turtle2/cmd_vel"), "/rt/turtle2/cmd_vel");
+    }
+
+    #[test]
+    fn zenoh_keys_take_precedence() {
+        let remaps = Remaps::parse(vec![
+            "/turtle1/cmd_vel:=/robot1/cmd_vel",
+            "/rt/turtle1/cmd_vel:=/custom/cmd_vel",
+        ])
+        .unwrap();
+        assert_eq!(remaps.apply("/rt/turtle1/cmd_vel"), "/custom/cmd_vel");
+        assert_eq!(remaps.apply("/rq/turtle1/cmd_vel"), "/rq/robot1/cmd_vel");
+    }
+
+    #[test]
+    fn rejects_invalid_remaps() {
+        assert!(Remaps::parse(vec!["/a"]).is_err());
+        assert!(Remaps::parse(vec![":=/b"]).is_err());
+        assert!(Remaps::parse(vec!["/a:="]).is_err());
+        assert!(Remaps::parse(vec!["/a:=/b", "/a:=/c"]).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use futures::prelude::*;
+use std::time::Duration;
+use zenoh::net::*;
+
+use crate::limits::Limits;
+use crate::msgs::Twist;
+
+// The size of a CDR encoded Twist (encapsulation header and 6 f64)
+const TWIST_SIZE: u64 = 4 + 6 * 8;
+
+// Wait at most `wait` for the velocity limits advertised by the robot on `topic` (`--robot-limits`).
+//
+// The robot publishes its maximum velocities as a geometry_msgs/Twist (linear.x and angular.z,
+// as absolute values), e.g. periodically or as a transient local topic.
+// Note: zenoh 0.5 doesn't provide liveliness tokens to attach such metadata to the robot.
+pub async fn receive(session: &Session, topic: &str, wait: Duration) -> Result<Limits, String> {
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+        period: None,
+    };
+    let mut subscriber = session
+        .declare_subscriber(&topic.into(), &sub_info)
+        .await
+        .map_err(|e| format!("failed to subscribe to {}: {}", topic, e))?;
+    let sample = async_std::future::timeout(wait, subscriber.receiver().next())
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| format!("none received on {} in {} ms", topic, wait.as_millis()))?;
+    let twist: Twist = cdr::deserialize_from(sample.payload, cdr::size::Bounded(TWIST_SIZE))
+        .map_err(|e| format!("failed to decode the Twist received on {}: {}", topic, e))?;
+    let limits = Limits {
+        linear: twist.linear.x.abs(),
+        angular: twist.angular.z.abs(),
+    };
+    // Note: a NaN limit would let any command pass the clamps
+    if limits.linear.is_nan() || limits.angular.is_nan() {
+        return Err(format!("invalid limits received on {}", topic));
+    }
+    Ok(limits)
+}
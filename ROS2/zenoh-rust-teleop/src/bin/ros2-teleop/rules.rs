@@ -0,0 +1,134 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use regex::Regex;
+use serde_derive::Deserialize;
+use std::time::{Duration, Instant};
+
+use crate::limits::Limits;
+use crate::maneuver::{Maneuver, Step};
+
+// A rule, as described in the rules file (JSON), e.g.:
+//   [
+//     {
+//       "regex": "I hit the wall",
+//       "maneuver": [
+//         { "linear": -1.0, "angular": 0.0, "duration": 1.0 },
+//         { "linear": 0.0, "angular": 1.5, "duration": 1.0 }
+//       ],
+//       "cooldown": 5.0
+//     }
+//   ]
+// The cooldown (in seconds) is optional and defaults to the `--rules-cooldown` value.
+#[derive(Deserialize)]
+struct RuleDesc {
+    regex: String,
+    maneuver: Vec<Step>,
+    cooldown: Option<f64>,
+}
+
+struct Rule {
+    regex: Regex,
+    maneuver: Vec<Step>,
+    cooldown: Duration,
+    last_triggered: Option<Instant>,
+}
+
+// The rules triggering a maneuver when a received log message matches their regex.
+//
+// To prevent loops (e.g. the maneuver making the robot log the same message again) or floods,
+// a rule can't be triggered again during its cooldown.
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+impl Rules {
+    pub fn load(file: &str, default_cooldown: f64, limits: &Limits) -> Result<Rules, String> {
+        let content = std::fs::read_to_string(file)
+            .map_err(|e| format!("Failed to read rules file {}: {}", file, e))?;
+        let descs: Vec<RuleDesc> = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse rules file {}: {}", file, e))?;
+        let rules = descs
+            .into_iter()
+            .map(|desc| {
+                let regex = Regex::new(&desc.regex)
+                    .map_err(|e| format!("Invalid regex '{}' in {}: {}", desc.regex, file, e))?;
+                if let Some(step) = desc.maneuver.iter().find(|s| !valid_duration(s.duration)) {
+                    return Err(format!(
+                        "Invalid duration {} for rule '{}' in {}",
+                        step.duration, desc.regex, file
+                    ));
+                }
+                for step in desc.maneuver.iter() {
+                    limits.check((step.linear, step.angular)).map_err(|e| {
+                        format!("Invalid step for rule '{}' in {}: {}", desc.regex, file, e)
+                    })?;
+                }
+                let cooldown = desc.cooldown.unwrap_or(default_cooldown);
+                if !valid_duration(cooldown) {
+                    return Err(format!(
+                        "Invalid cooldown {} for rule '{}' in {}",
+                        cooldown, desc.regex, file
+                    ));
+                }
+                Ok(Rule {
+                    regex,
+                    maneuver: desc.maneuver,
+                    cooldown: Duration::from_secs_f64(cooldown),
+                    last_triggered: None,
+                })
+            })
+            .collect::<Result<Vec<Rule>, String>>()?;
+        Ok(Rules { rules })
+    }
+
+    // Check the maneuvers against new limits (e.g. advertised by the robot)
+    pub fn check(&self, limits: &Limits) -> Result<(), String> {
+        for rule in self.rules.iter() {
+            for step in rule.maneuver.iter() {
+                limits
+                    .check((step.linear, step.angular))
+                    .map_err(|e| format!("Invalid step for rule '{}': {}", rule.regex, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    // The maneuver of the 1st rule matching the message and not in cooldown, if any
+    pub fn trigger(&mut self, msg: &str) -> Option<Maneuver> {
+        let now = Instant::now();
+        for (i, rule) in self.rules.iter_mut().enumerate() {
+            if let Some(last) = rule.last_triggered {
+                if now.duration_since(last) < rule.cooldown {
+                    continue;
+                }
+            }
+            if rule.regex.is_match(msg) {
+                rule.last_triggered = Some(now);
+                return Some(Maneuver::new(
+                    format!("rule #{} '{}'", i + 1, rule.regex),
+                    rule.maneuver.clone(),
+                ));
+            }
+        }
+        None
+    }
+}
+
+fn valid_duration(seconds: f64) -> bool {
+    seconds.is_finite() && seconds >= 0.0
+}
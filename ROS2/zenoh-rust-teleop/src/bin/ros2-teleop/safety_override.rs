@@ -0,0 +1,116 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::time::{Duration, Instant};
+
+// The delay to confirm the override, by pressing the key again
+const CONFIRM_DELAY: Duration = Duration::from_secs(3);
+// The period of the flashing banner
+const BLINK_PERIOD: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum State {
+    Off,
+    // The key was pressed once: waiting the confirmation until the deadline
+    Confirming(Instant),
+    // The limits are lifted until the expiry
+    Active(Instant),
+}
+
+// The override of the safety limits (`--override-secs`), lifting the velocity limits and the
+// deadman timer during a short window.
+//
+// To not be triggered accidentally, it's activated by pressing CTRL+O twice within CONFIRM_DELAY,
+// and always expires after its duration (pressing CTRL+O again ends it earlier).
+pub struct Override {
+    duration: Duration,
+    state: State,
+    next_blink: Instant,
+    blink_on: bool,
+}
+
+impl Override {
+    pub fn new(duration: Duration) -> Self {
+        Override {
+            duration,
+            state: State::Off,
+            next_blink: Instant::now(),
+            blink_on: false,
+        }
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, State::Active(_))
+    }
+
+    // CTRL+O pressed
+    pub fn on_key(&mut self) {
+        let now = Instant::now();
+        self.state = match self.state {
+            State::Off => State::Confirming(now + CONFIRM_DELAY),
+            State::Confirming(_) => {
+                self.next_blink = now;
+                State::Active(now + self.duration)
+            }
+            State::Active(_) => State::Off,
+        };
+    }
+
+    // The time of the next state transition or banner blink, if any
+    pub fn next_deadline(&self) -> Option<Instant> {
+        match self.state {
+            State::Off => None,
+            State::Confirming(deadline) => Some(deadline),
+            State::Active(expiry) => Some(expiry.min(self.next_blink)),
+        }
+    }
+
+    // Update the state at the deadline
+    pub fn on_deadline(&mut self) {
+        let now = Instant::now();
+        match self.state {
+            State::Confirming(deadline) | State::Active(deadline) if deadline <= now => {
+                self.state = State::Off
+            }
+            State::Active(_) => {
+                self.blink_on = !self.blink_on;
+                self.next_blink = now + BLINK_PERIOD;
+            }
+            _ => (),
+        }
+    }
+
+    // The status message corresponding to the state
+    pub fn banner(&self) -> String {
+        match self.state {
+            State::Off => String::new(),
+            State::Confirming(_) => format!(
+                "Press CTRL+O again within {} s to lift the safety limits for {} s",
+                CONFIRM_DELAY.as_secs(),
+                self.duration.as_secs_f64()
+            ),
+            State::Active(expiry) if self.blink_on => format!(
+                "*** OVERRIDE: no velocity limits nor deadman ({:.0} s left, CTRL+O to end) ***",
+                expiry
+                    .saturating_duration_since(Instant::now())
+                    .as_secs_f64()
+                    .ceil()
+            ),
+            State::Active(_) => String::new(),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+
+// The function of the script, called with the input axes
+const FUNCTION: &str = "transform";
+// The maximum number of operations of a call, so an endless loop can't freeze the teleop
+const MAX_OPERATIONS: u64 = 100_000;
+
+// A Rhai script transforming the operator's commands (`--transform-script`): its function
+// `transform(forward, turn)` returns the velocities as a map `#{ linear: .., angular: .. }`.
+pub struct TransformScript {
+    engine: Engine,
+    ast: AST,
+}
+
+impl TransformScript {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let ast = engine
+            .compile_file(path.into())
+            .map_err(|e| format!("Invalid --transform-script {}: {}", path, e))?;
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == FUNCTION && f.params.len() == 2)
+        {
+            return Err(format!(
+                "Invalid --transform-script {}: no function {}(forward, turn)",
+                path, FUNCTION
+            ));
+        }
+        Ok(TransformScript { engine, ast })
+    }
+
+    pub fn apply(&self, (forward, turn): (f64, f64)) -> Result<(f64, f64), String> {
+        let result: Map = self
+            .engine
+            .call_fn(&mut Scope::new(), &self.ast, FUNCTION, (forward, turn))
+            .map_err(|e| format!("transform script failed: {}", e))?;
+        let velocity = |name: &str| {
+            result
+                .get(name)
+                .and_then(to_f64)
+                .ok_or_else(|| format!("transform script returned no number for '{}'", name))
+        };
+        Ok((velocity("linear")?, velocity("angular")?))
+    }
+}
+
+// A number returned by the script, either a float or an integer
+fn to_f64(value: &Dynamic) -> Option<f64> {
+    value
+        .as_float()
+        .ok()
+        .or_else(|| value.as_int().ok().map(|i| i as f64))
+}
@@ -0,0 +1,85 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::channel::Sender;
+use async_std::fs::File;
+use futures::prelude::*;
+
+use crate::joystick::apply_deadzone;
+use crate::InputEvent;
+
+// The HID reports of the 3Dconnexion devices
+const REPORT_TRANSLATION: u8 = 1;
+const REPORT_ROTATION: u8 = 2;
+
+// The approximate full deflection of an axis
+const AXIS_RANGE: f64 = 350.0;
+
+// The 6 axes of a SpaceMouse: translations X, Y, Z then rotations X, Y, Z
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SpaceMouseEvent {
+    pub axes: [i16; 6],
+}
+
+impl SpaceMouseEvent {
+    // An axis scaled to [-1.0, 1.0], with the deadzone applied
+    pub fn axis(&self, number: usize, deadzone: f64) -> f64 {
+        let value = (self.axes[number] as f64 / AXIS_RANGE).clamp(-1.0, 1.0);
+        apply_deadzone(value, deadzone)
+    }
+}
+
+// Read the HID reports of a SpaceMouse from its hidraw device (e.g. "/dev/hidraw0"),
+// sending the state of its axes to the input channel at each motion.
+//
+// The translations and rotations are reported separately (report ids 1 and 2, each with 3 i16),
+// except by the recent devices reporting all 6 axes in the translation report.
+pub async fn read_events(device: String, sender: Sender<InputEvent>) {
+    let mut file = match File::open(&device).await {
+        Ok(file) => file,
+        Err(e) => {
+            log::error!("Failed to open SpaceMouse device {}: {}", device, e);
+            return;
+        }
+    };
+    let mut state = SpaceMouseEvent::default();
+    let mut buf = [0u8; 64];
+    loop {
+        // Note: a read on a hidraw device returns a single report
+        let len = match file.read(&mut buf).await {
+            Ok(0) => {
+                log::error!("SpaceMouse device {} closed", device);
+                return;
+            }
+            Ok(len) => len,
+            Err(e) => {
+                log::error!("Failed to read SpaceMouse device {}: {}", device, e);
+                return;
+            }
+        };
+        let values: Vec<i16> = buf[1..len]
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        match (buf[0], values.len()) {
+            (REPORT_TRANSLATION, n) if n >= 6 => state.axes.copy_from_slice(&values[..6]),
+            (REPORT_TRANSLATION, n) if n >= 3 => state.axes[..3].copy_from_slice(&values[..3]),
+            (REPORT_ROTATION, n) if n >= 3 => state.axes[3..].copy_from_slice(&values[..3]),
+            // e.g. the buttons report
+            _ => continue,
+        }
+        if let Err(e) = sender.send(InputEvent::SpaceMouse(state)).await {
+            log::warn!("Failed to push SpaceMouse Event: {}", e);
+        }
+    }
+}
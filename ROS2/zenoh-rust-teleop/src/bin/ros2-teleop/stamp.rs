@@ -0,0 +1,248 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use cdr::{CdrLe, Infinite};
+use std::cell::Cell;
+use std::io::Read;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::msgs::{Header, SeqHeader, SeqTwistStamped, Time, Twist, TwistStamped};
+
+// The headers of the stamped messages (`--stamped`).
+//
+// With `--use-sim-time`, the messages are stamped with the latest time received on the `/clock` topic,
+// falling back to the system time until the first clock message arrives.
+// With `--header-seq`, the headers start with a sequence number (as in ROS1), increased by each
+// stamped message of any target.
+// With `--broadcast-lead-ms`, the messages are stamped with their execution time, ahead of the
+// (system or simulation) time.
+pub struct Stamper {
+    frame_id: String,
+    use_sim_time: bool,
+    sim_time: Option<Time>,
+    // the sequence number of the next header, if included
+    seq: Option<Cell<u32>>,
+    lead: Duration,
+}
+
+impl Stamper {
+    pub fn new(frame_id: String, use_sim_time: bool) -> Self {
+        Stamper {
+            frame_id,
+            use_sim_time,
+            sim_time: None,
+            seq: None,
+            lead: Duration::ZERO,
+        }
+    }
+
+    pub fn with_seq(mut self) -> Self {
+        self.seq = Some(Cell::new(0));
+        self
+    }
+
+    pub fn with_lead(mut self, lead: Duration) -> Self {
+        self.lead = lead;
+        self
+    }
+
+    // The sequence number of the next header, if included
+    pub fn next_seq(&self) -> Option<u32> {
+        self.seq.as_ref().map(Cell::get)
+    }
+
+    pub fn set_sim_time(&mut self, time: Time) {
+        self.sim_time = Some(time);
+    }
+
+    pub fn header(&self) -> Header {
+        let stamp = match self.sim_time {
+            Some(time) if self.use_sim_time => time,
+            _ => system_time(),
+        };
+        let nanosec = stamp.nanosec as u64 + self.lead.subsec_nanos() as u64;
+        let stamp = Time {
+            sec: stamp.sec + self.lead.as_secs() as i32 + (nanosec / 1_000_000_000) as i32,
+            nanosec: (nanosec % 1_000_000_000) as u32,
+        };
+        Header {
+            stamp,
+            frame_id: self.frame_id.clone(),
+        }
+    }
+
+    // Encode a new stamped message of the Twist
+    pub fn encode(&self, twist: Twist) -> Vec<u8> {
+        let header = self.header();
+        match self.seq.as_ref() {
+            Some(seq) => {
+                let stamped = SeqTwistStamped {
+                    header: SeqHeader {
+                        seq: seq.get(),
+                        stamp: header.stamp,
+                        frame_id: header.frame_id,
+                    },
+                    twist,
+                };
+                seq.set(seq.get().wrapping_add(1));
+                cdr::serialize::<_, _, CdrLe>(&stamped, Infinite).unwrap()
+            }
+            None => {
+                cdr::serialize::<_, _, CdrLe>(&TwistStamped { header, twist }, Infinite).unwrap()
+            }
+        }
+    }
+
+    // Decode a stamped message, with the same header layout, and its header's seq if included
+    pub fn decode<R: Read>(&self, reader: R) -> Result<(Option<u32>, Twist), String> {
+        if self.seq.is_some() {
+            cdr::deserialize_from::<_, SeqTwistStamped, _>(reader, Infinite)
+                .map(|stamped| (Some(stamped.header.seq), stamped.twist))
+                .map_err(|e| format!("failed to decode TwistStamped (with header seq): {}", e))
+        } else {
+            cdr::deserialize_from::<_, TwistStamped, _>(reader, Infinite)
+                .map(|stamped| (None, stamped.twist))
+                .map_err(|e| format!("failed to decode TwistStamped: {}", e))
+        }
+    }
+}
+
+pub fn system_time() -> Time {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Time {
+        sec: now.as_secs() as i32,
+        nanosec: now.subsec_nanos(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msgs::Vector3;
+    use std::convert::TryInto;
+
+    fn twist(linear: f64, angular: f64) -> Twist {
+        Twist {
+            linear: Vector3 {
+                x: linear,
+                y: 0.0,
+                z: 0.0,
+            },
+            angular: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: angular,
+            },
+        }
+    }
+
+    // A stamper with a fixed (simulation) time
+    fn stamper(frame_id: &str) -> Stamper {
+        let mut stamper = Stamper::new(frame_id.to_string(), true);
+        stamper.set_sim_time(Time {
+            sec: 12,
+            nanosec: 345,
+        });
+        stamper
+    }
+
+    fn u32_at(buf: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn f64_at(buf: &[u8], offset: usize) -> f64 {
+        f64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn header_layout() {
+        let buf = stamper("base").encode(twist(1.5, -0.5));
+        assert_eq!(buf[..4], [0x00, 0x01, 0x00, 0x00]);
+        assert_eq!(u32_at(&buf, 4), 12);
+        assert_eq!(u32_at(&buf, 8), 345);
+        // the frame_id's length includes the terminator
+        assert_eq!(u32_at(&buf, 12), 5);
+        assert_eq!(&buf[16..21], b"base\0");
+        // the doubles are aligned on 8 bytes (after the 4-byte encapsulation header)
+        assert_eq!(buf[21..28], [0; 7]);
+        assert_eq!(f64_at(&buf, 28), 1.5);
+        assert_eq!(f64_at(&buf, 28 + 5 * 8), -0.5);
+        assert_eq!(buf.len(), 28 + 6 * 8);
+    }
+
+    #[test]
+    fn header_layout_with_seq() {
+        let buf = stamper("").with_seq().encode(twist(1.5, -0.5));
+        assert_eq!(buf[..4], [0x00, 0x01, 0x00, 0x00]);
+        // the seq comes first, as a u32
+        assert_eq!(u32_at(&buf, 4), 0);
+        assert_eq!(u32_at(&buf, 8), 12);
+        assert_eq!(u32_at(&buf, 12), 345);
+        assert_eq!(u32_at(&buf, 16), 1);
+        assert_eq!(buf[20], 0);
+        assert_eq!(buf[21..28], [0; 7]);
+        assert_eq!(f64_at(&buf, 28), 1.5);
+        assert_eq!(f64_at(&buf, 28 + 5 * 8), -0.5);
+        assert_eq!(buf.len(), 28 + 6 * 8);
+    }
+
+    #[test]
+    fn round_trip() {
+        let stamper = stamper("base");
+        let (seq, decoded) = stamper
+            .decode(&stamper.encode(twist(1.5, -0.5))[..])
+            .unwrap();
+        assert_eq!(seq, None);
+        assert_eq!((decoded.linear.x, decoded.angular.z), (1.5, -0.5));
+    }
+
+    #[test]
+    fn round_trip_with_seq() {
+        let stamper = stamper("base").with_seq();
+        let (seq, decoded) = stamper
+            .decode(&stamper.encode(twist(1.5, -0.5))[..])
+            .unwrap();
+        assert_eq!(seq, Some(0));
+        assert_eq!((decoded.linear.x, decoded.angular.z), (1.5, -0.5));
+    }
+
+    #[test]
+    fn seq_increments() {
+        let stamper = stamper("base").with_seq();
+        assert_eq!(stamper.next_seq(), Some(0));
+        for expected in 0..3 {
+            let buf = stamper.encode(twist(1.0, 0.0));
+            assert_eq!(u32_at(&buf, 4), expected);
+        }
+        assert_eq!(stamper.next_seq(), Some(3));
+    }
+
+    #[test]
+    fn lead_carries_the_nanoseconds() {
+        let mut stamper = stamper("base").with_lead(Duration::from_millis(1500));
+        stamper.set_sim_time(Time {
+            sec: 12,
+            nanosec: 800_000_000,
+        });
+        let header = stamper.header();
+        assert_eq!(
+            header.stamp,
+            Time {
+                sec: 14,
+                nanosec: 300_000_000
+            }
+        );
+    }
+}
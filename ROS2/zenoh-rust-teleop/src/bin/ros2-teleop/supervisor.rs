@@ -0,0 +1,51 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use cdr::Infinite;
+use zenoh::net::ZBuf;
+
+use crate::msgs::Bool;
+
+// The gating of the commands by a supervisory system (`--enable-topic`), publishing
+// std_msgs/Bool messages to enable or disable the teleop.
+//
+// Note: the teleop is disabled until the first message, so it can't move the robot
+// if the supervisor isn't running.
+pub struct Supervisor {
+    pub topic: String,
+    enabled: bool,
+}
+
+impl Supervisor {
+    pub fn new(topic: String) -> Self {
+        Supervisor {
+            topic,
+            enabled: false,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // Decode a message of the enable topic, returning the new state if it changed
+    pub fn on_sample(&mut self, payload: ZBuf) -> Result<Option<bool>, String> {
+        let msg: Bool = cdr::deserialize_from(payload, Infinite)
+            .map_err(|e| format!("Error decoding Bool on {}: {}", self.topic, e))?;
+        if msg.data == self.enabled {
+            return Ok(None);
+        }
+        self.enabled = msg.data;
+        Ok(Some(self.enabled))
+    }
+}
@@ -0,0 +1,85 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+
+#[derive(Default)]
+struct TargetStats {
+    sent: u64,
+    failed: u64,
+    // the error of the last publication, if it failed
+    last_error: Option<String>,
+}
+
+// The results of the publications per target topic (the leader and the follower).
+//
+// A failed publication to a target doesn't prevent the publications to the others: the target
+// is reported as failing until a publication to it succeeds again.
+// Note: only the local errors are observable, as zenoh 0.5's write_ext() doesn't report the delivery
+// (it always succeeds in this version): a target whose subscriber has gone away isn't failing.
+#[derive(Default)]
+pub struct Targets {
+    targets: Vec<(String, TargetStats)>,
+}
+
+impl Targets {
+    // Record the result of a publication, returning true if the target started or stopped failing
+    pub fn record(&mut self, topic: &str, result: &Result<usize, String>) -> bool {
+        let stats = match self.targets.iter().position(|(t, _)| t == topic) {
+            Some(i) => &mut self.targets[i].1,
+            None => {
+                self.targets
+                    .push((topic.to_string(), TargetStats::default()));
+                &mut self.targets.last_mut().unwrap().1
+            }
+        };
+        let was_failing = stats.last_error.is_some();
+        match result {
+            Ok(_) => {
+                stats.sent += 1;
+                stats.last_error = None;
+            }
+            Err(e) => {
+                stats.failed += 1;
+                stats.last_error = Some(e.clone());
+            }
+        }
+        was_failing != stats.last_error.is_some()
+    }
+
+    // The targets whose last publication failed
+    pub fn failing(&self) -> Vec<String> {
+        self.targets
+            .iter()
+            .filter(|(_, stats)| stats.last_error.is_some())
+            .map(|(topic, _)| topic.clone())
+            .collect()
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.targets.iter().any(|(_, stats)| stats.failed > 0)
+    }
+
+    // The summary printed at exit, as 1 line per target
+    pub fn summary(&self) -> Vec<String> {
+        self.targets
+            .iter()
+            .map(|(topic, stats)| match stats.last_error.as_ref() {
+                Some(e) => format!(
+                    "  {}: {} sent, {} failed (FAILING: {})",
+                    topic, stats.sent, stats.failed, e
+                ),
+                None => format!("  {}: {} sent, {} failed", topic, stats.sent, stats.failed),
+            })
+            .collect()
+    }
+}
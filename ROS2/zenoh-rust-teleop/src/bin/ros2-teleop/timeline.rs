@@ -0,0 +1,245 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::msgs::Log;
+
+// The duration of the intervals summarizing the commands
+const INTERVAL: Duration = Duration::from_secs(10);
+// The maximum number of characters of an event's text in the report
+const MAX_TEXT_CHARS: usize = 200;
+
+// The kind of an event of the timeline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventKind {
+    // the deadman timer, the safety override, the supervisor, the rejected commands...
+    Safety,
+    Connectivity,
+    Maneuver,
+    // the ERROR and FATAL logs received
+    Log,
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            EventKind::Safety => "safety",
+            EventKind::Connectivity => "connectivity",
+            EventKind::Maneuver => "maneuver",
+            EventKind::Log => "log",
+        };
+        f.write_str(name)
+    }
+}
+
+struct Event {
+    time: Duration,
+    kind: EventKind,
+    text: String,
+}
+
+// The commands published during an interval
+#[derive(Default)]
+struct Interval {
+    sent: u64,
+    repeated: u64,
+    clamped: u64,
+    max_linear: f64,
+    max_angular: f64,
+}
+
+// The timeline of the session (`--timeline-report`), written on exit as a markdown report
+// for an incident review: the commands over time, the events, and the log severity counts.
+pub struct Timeline {
+    path: String,
+    cmd_vel: String,
+    start: Instant,
+    start_time: Duration,
+    // the intervals since the start, the last one in progress
+    intervals: Vec<Interval>,
+    events: Vec<Event>,
+    // the number of logs received by severity level
+    log_levels: BTreeMap<u8, (&'static str, u64)>,
+}
+
+impl Timeline {
+    // Check that the report can be created, as it's only written on exit
+    pub fn create(path: &str, cmd_vel: &str) -> Result<Self, String> {
+        File::create(path)
+            .map_err(|e| format!("Failed to create timeline report {}: {}", path, e))?;
+        Ok(Timeline {
+            path: path.to_string(),
+            cmd_vel: cmd_vel.to_string(),
+            start: Instant::now(),
+            start_time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+            intervals: vec![],
+            events: vec![],
+            log_levels: BTreeMap::new(),
+        })
+    }
+
+    pub fn event(&mut self, kind: EventKind, text: &str) {
+        self.events.push(Event {
+            time: self.start.elapsed(),
+            kind,
+            text: text.to_string(),
+        });
+    }
+
+    // A published command, and whether it's a republication or was clamped by the limits
+    pub fn command(&mut self, (linear, angular): (f64, f64), repeated: bool, clamped: bool) {
+        let index = (self.start.elapsed().as_secs() / INTERVAL.as_secs()) as usize;
+        if self.intervals.len() <= index {
+            self.intervals.resize_with(index + 1, Interval::default);
+        }
+        let interval = &mut self.intervals[index];
+        interval.sent += 1;
+        interval.repeated += repeated as u64;
+        interval.clamped += clamped as u64;
+        interval.max_linear = interval.max_linear.max(linear.abs());
+        interval.max_angular = interval.max_angular.max(angular.abs());
+    }
+
+    pub fn log(&mut self, log: &Log) {
+        self.log_levels
+            .entry(log.level)
+            .or_insert((log.level_name(), 0))
+            .1 += 1;
+        if log.level >= 40 {
+            self.event(EventKind::Log, &log.to_string());
+        }
+    }
+
+    // Write the report, with the summary lines of the publications
+    pub fn write(self, publications: &[String]) -> Result<String, String> {
+        let path = self.path.clone();
+        self.write_to(publications)
+            .map_err(|e| format!("Failed to write timeline report {}: {}", path, e))?;
+        Ok(path)
+    }
+
+    fn write_to(self, publications: &[String]) -> std::io::Result<()> {
+        let mut out = BufWriter::new(File::create(&self.path)?);
+        let duration = self.start.elapsed();
+        writeln!(out, "# Teleop session timeline")?;
+        writeln!(out)?;
+        writeln!(out, "- cmd_vel: `{}`", self.cmd_vel)?;
+        writeln!(
+            out,
+            "- start: {}.{:03} (seconds since the Unix epoch)",
+            self.start_time.as_secs(),
+            self.start_time.subsec_millis()
+        )?;
+        writeln!(out, "- duration: {}", format_time(duration))?;
+        writeln!(out)?;
+
+        writeln!(out, "## Commands")?;
+        writeln!(out)?;
+        if self.intervals.is_empty() {
+            writeln!(out, "No command published.")?;
+        } else {
+            writeln!(
+                out,
+                "| interval | sent | repeated | clamped | max \\|linear\\| | max \\|angular\\| |"
+            )?;
+            writeln!(out, "|---|---:|---:|---:|---:|---:|")?;
+            // Note: the intervals without commands are skipped
+            for (i, interval) in self.intervals.iter().enumerate() {
+                if interval.sent == 0 {
+                    continue;
+                }
+                let from = INTERVAL * i as u32;
+                writeln!(
+                    out,
+                    "| {} - {} | {} | {} | {} | {:.2} | {:.2} |",
+                    format_time(from),
+                    format_time(from + INTERVAL),
+                    interval.sent,
+                    interval.repeated,
+                    interval.clamped,
+                    interval.max_linear,
+                    interval.max_angular
+                )?;
+            }
+        }
+        writeln!(out)?;
+
+        writeln!(out, "## Events")?;
+        writeln!(out)?;
+        if self.events.is_empty() {
+            writeln!(out, "No event.")?;
+        } else {
+            writeln!(out, "| time | kind | event |")?;
+            writeln!(out, "|---|---|---|")?;
+            for event in self.events.iter() {
+                writeln!(
+                    out,
+                    "| {} | {} | {} |",
+                    format_time(event.time),
+                    event.kind,
+                    format_text(&event.text)
+                )?;
+            }
+        }
+        writeln!(out)?;
+
+        writeln!(out, "## Logs")?;
+        writeln!(out)?;
+        if self.log_levels.is_empty() {
+            writeln!(out, "No log received.")?;
+        } else {
+            writeln!(out, "| level | count |")?;
+            writeln!(out, "|---|---:|")?;
+            for (name, count) in self.log_levels.values() {
+                writeln!(out, "| {} | {} |", name, count)?;
+            }
+        }
+        writeln!(out)?;
+
+        writeln!(out, "## Publications")?;
+        writeln!(out)?;
+        for line in publications {
+            writeln!(out, "- {}", line.trim())?;
+        }
+        out.flush()
+    }
+}
+
+// An event's text as a table cell: truncated, on a single line, and without column separator
+fn format_text(text: &str) -> String {
+    let mut cell: String = text.chars().take(MAX_TEXT_CHARS).collect();
+    if cell.len() < text.len() {
+        cell.push('…');
+    }
+    cell.replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+        .replace('|', "\\|")
+}
+
+// A time since the start of the session, e.g. "01:02.345"
+fn format_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    format!(
+        "{:02}:{:02}.{:03}",
+        secs / 60,
+        secs % 60,
+        time.subsec_millis()
+    )
+}
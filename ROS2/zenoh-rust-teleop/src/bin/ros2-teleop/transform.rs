@@ -0,0 +1,106 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::str::FromStr;
+
+// The matrix mapping the input axes (forward, turn) to the published velocities (linear, angular)
+// (`--transform`), applied to the operator's commands before clamping:
+//   linear  = m[0][0] * forward + m[0][1] * turn
+//   angular = m[1][0] * forward + m[1][1] * turn
+// e.g. "1,0;0,-1" inverts the turns, "0,1;1,0" swaps the axes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    m: [[f64; 2]; 2],
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            m: [[1.0, 0.0], [0.0, 1.0]],
+        }
+    }
+}
+
+impl Transform {
+    pub fn apply(&self, (forward, turn): (f64, f64)) -> (f64, f64) {
+        (
+            self.m[0][0] * forward + self.m[0][1] * turn,
+            self.m[1][0] * forward + self.m[1][1] * turn,
+        )
+    }
+}
+
+impl FromStr for Transform {
+    type Err = String;
+
+    // Parse the rows separated by ';', of coefficients separated by ','
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| format!("Invalid --transform '{}': {}", s, reason);
+        let rows: Vec<Vec<f64>> = s
+            .split(';')
+            .map(|row| {
+                row.split(',')
+                    .map(|c| c.trim().parse::<f64>())
+                    .collect::<Result<Vec<f64>, _>>()
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|e| invalid(&e.to_string()))?;
+        if rows.len() != 2 || rows.iter().any(|row| row.len() != 2) {
+            return Err(invalid("expected a 2x2 matrix, as \"1,0;0,1\""));
+        }
+        if rows.iter().flatten().any(|c| !c.is_finite()) {
+            return Err(invalid("the coefficients must be finite"));
+        }
+        Ok(Transform {
+            m: [[rows[0][0], rows[0][1]], [rows[1][0], rows[1][1]]],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_the_identity() {
+        let transform: Transform = "1,0;0,1".parse().unwrap();
+        assert_eq!(transform, Transform::default());
+        assert_eq!(transform.apply((2.0, -1.0)), (2.0, -1.0));
+    }
+
+    #[test]
+    fn inverts_swaps_and_mixes() {
+        let invert: Transform = "1,0;0,-1".parse().unwrap();
+        assert_eq!(invert.apply((2.0, 1.0)), (2.0, -1.0));
+        let swap: Transform = "0,1;1,0".parse().unwrap();
+        assert_eq!(swap.apply((2.0, 1.0)), (1.0, 2.0));
+        let mix: Transform = " 1, 0.5 ; 0, 2 ".parse().unwrap();
+        assert_eq!(mix.apply((2.0, 1.0)), (2.5, 2.0));
+    }
+
+    #[test]
+    fn rejects_invalid_matrices() {
+        for invalid in [
+            "",
+            "1,0",
+            "1,0;0",
+            "1,0,0;0,1",
+            "1,0;0,1;0,0",
+            "a,0;0,1",
+            "NaN,0;0,1",
+            "inf,0;0,1",
+        ] {
+            assert!(invalid.parse::<Transform>().is_err(), "{}", invalid);
+        }
+    }
+}
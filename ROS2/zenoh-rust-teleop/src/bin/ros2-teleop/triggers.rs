@@ -0,0 +1,123 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use cdr::{CdrLe, Infinite};
+use std::fmt;
+
+use crate::msgs::{Bool, Empty, StringMsg};
+
+// The keys already bound to teleop commands
+const RESERVED_KEYS: [char; 4] = ['q', 'c', 'r', ' '];
+
+enum Message {
+    // the value is toggled at each press if `toggle` is true
+    Bool { value: bool, toggle: bool },
+    Empty,
+    String(String),
+}
+
+// A key publishing a message on an auxiliary topic of the robot (horn, lights...) when pressed.
+pub struct Trigger {
+    pub key: char,
+    pub topic: String,
+    message: Message,
+}
+
+impl Trigger {
+    // Parse a trigger binding: "KEY:TOPIC:TYPE[:VALUE]" where TYPE is one of:
+    //  - "bool", with VALUE "true", "false" or "toggle" (default)
+    //  - "empty", without VALUE
+    //  - "string", with VALUE the published string (which can include ':')
+    pub fn parse(binding: &str) -> Result<Trigger, String> {
+        let mut parts = binding.splitn(4, ':');
+        let (key, topic, kind, value) = (parts.next(), parts.next(), parts.next(), parts.next());
+        let key = match key.map(|k| k.chars().collect::<Vec<char>>()).as_deref() {
+            Some([key]) if !RESERVED_KEYS.contains(&key.to_ascii_lowercase()) => {
+                key.to_ascii_lowercase()
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid trigger '{}': KEY must be a single character other than {:?}",
+                    binding, RESERVED_KEYS
+                ))
+            }
+        };
+        let topic = match topic {
+            Some(topic) if !topic.is_empty() => topic.to_string(),
+            _ => return Err(format!("Invalid trigger '{}': missing TOPIC", binding)),
+        };
+        let message = match (kind, value) {
+            (Some("bool"), None) | (Some("bool"), Some("toggle")) => Message::Bool {
+                value: true,
+                toggle: true,
+            },
+            (Some("bool"), Some(value)) => Message::Bool {
+                value: value
+                    .parse()
+                    .map_err(|_| format!("Invalid trigger '{}': invalid bool value", binding))?,
+                toggle: false,
+            },
+            (Some("empty"), None) => Message::Empty,
+            (Some("string"), value) => Message::String(value.unwrap_or_default().to_string()),
+            _ => {
+                return Err(format!(
+                    "Invalid trigger '{}': expected KEY:TOPIC:TYPE[:VALUE] with TYPE one of bool, empty or string",
+                    binding
+                ))
+            }
+        };
+        Ok(Trigger {
+            key,
+            topic,
+            message,
+        })
+    }
+
+    // The CDR encoded message to publish on a key press
+    pub fn press(&mut self) -> Vec<u8> {
+        let encoded = match &mut self.message {
+            Message::Bool { value, toggle } => {
+                let encoded = cdr::serialize::<_, _, CdrLe>(&Bool { data: *value }, Infinite);
+                if *toggle {
+                    *value = !*value;
+                }
+                encoded
+            }
+            Message::Empty => cdr::serialize::<_, _, CdrLe>(
+                &Empty {
+                    structure_needs_at_least_one_member: 0,
+                },
+                Infinite,
+            ),
+            Message::String(data) => {
+                cdr::serialize::<_, _, CdrLe>(&StringMsg { data: data.clone() }, Infinite)
+            }
+        };
+        encoded.unwrap()
+    }
+}
+
+// Displays the last published message (i.e. for a toggle, the opposite of the current value)
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            Message::Bool {
+                value,
+                toggle: true,
+            } => write!(f, "{} <- Bool {}", self.topic, !value),
+            Message::Bool { value, .. } => write!(f, "{} <- Bool {}", self.topic, value),
+            Message::Empty => write!(f, "{} <- Empty", self.topic),
+            Message::String(data) => write!(f, "{} <- String '{}'", self.topic, data),
+        }
+    }
+}
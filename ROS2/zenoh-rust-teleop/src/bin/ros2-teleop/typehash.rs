@@ -0,0 +1,58 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+
+// The DDS type names of the messages, as in the keys including the type hash
+pub const TWIST_TYPE: &str = "geometry_msgs::msg::dds_::Twist_";
+pub const TWIST_STAMPED_TYPE: &str = "geometry_msgs::msg::dds_::TwistStamped_";
+pub const LOG_TYPE: &str = "rcl_interfaces::msg::dds_::Log_";
+
+// Check a type hash (`--type-hash`), as displayed by `ros2 topic info --verbose` since ROS2 Iron
+// (e.g. "RIHS01_" followed by 64 hexadecimal digits)
+pub fn check(hash: &str) -> Result<(), String> {
+    match hash.strip_prefix("RIHS01_") {
+        Some(hex) if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) => Ok(()),
+        _ => Err(format!(
+            "Invalid --type-hash {}: expected RIHS01_ followed by 64 hexadecimal digits",
+            hash
+        )),
+    }
+}
+
+// The key of a topic with its type and type hash ("*" to match any hash, e.g. for a subscription)
+pub fn typed_key(key: &str, type_name: &str, hash: &str) -> String {
+    format!("{}/{}/{}", key.trim_end_matches('/'), type_name, hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_the_rihs01_hashes() {
+        let hex = "a".repeat(62) + "0F";
+        assert!(check(&format!("RIHS01_{}", hex)).is_ok());
+        assert!(check(&hex).is_err());
+        assert!(check(&format!("RIHS02_{}", hex)).is_err());
+        assert!(check(&format!("RIHS01_{}", &hex[1..])).is_err());
+        assert!(check(&format!("RIHS01_{}g", &hex[1..])).is_err());
+    }
+
+    #[test]
+    fn appends_the_type_and_hash() {
+        assert_eq!(
+            typed_key("/rt/turtle1/cmd_vel/", TWIST_TYPE, "*"),
+            "/rt/turtle1/cmd_vel/geometry_msgs::msg::dds_::Twist_/*"
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//
+// Copyright (c) 2021 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use futures::prelude::*;
+use std::time::{Duration, Instant};
+use zenoh::net::*;
+use zenoh::Properties;
+
+use crate::metrics::Metrics;
+use crate::msgs::Twist;
+use crate::qos::QosProfile;
+use crate::record::Record;
+use crate::stamp::Stamper;
+
+// The maximum time to wait for the echo session to connect
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+// The time to wait for the last echoed commands, after the replay
+const SETTLE_DELAY: Duration = Duration::from_secs(1);
+
+// The options of the `--replay-verify` mode
+pub struct VerifyArgs {
+    pub records: Vec<Record>,
+    pub tolerance: f64,
+}
+
+// An echoed command, with its header's seq if included
+type Echoed = Result<(Option<u32>, (f64, f64)), String>;
+
+fn decode(payload: ZBuf, stamper: Option<&Stamper>) -> Echoed {
+    let (seq, twist): (Option<u32>, Twist) = if let Some(stamper) = stamper {
+        stamper
+            .decode(payload)
+            .map_err(|e| format!("echoed command: {}", e))?
+    } else {
+        let twist = cdr::deserialize_from(payload, cdr::size::Infinite)
+            .map_err(|e| format!("failed to decode echoed Twist: {}", e))?;
+        (None, twist)
+    };
+    Ok((seq, (twist.linear.x, twist.angular.z)))
+}
+
+fn matches(record: &Record, (linear, angular): (f64, f64), tolerance: f64) -> bool {
+    (linear - record.linear).abs() <= tolerance && (angular - record.angular).abs() <= tolerance
+}
+
+// The ranges of missing seqs, e.g. "5-7, 12"
+fn format_gaps(missing: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = vec![];
+    for &seq in missing {
+        match ranges.last_mut() {
+            Some((_, last)) if last.wrapping_add(1) == seq => *last = seq,
+            _ => ranges.push((seq, seq)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(from, to)| {
+            if from == to {
+                from.to_string()
+            } else {
+                format!("{}-{}", from, to)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// Check the echoed commands by order: the n-th observed must be the n-th recorded
+fn check_by_order(records: &[Record], observed: &[Echoed], tolerance: f64, cmd_vel: &str) -> bool {
+    let mut mismatches = 0;
+    for (i, (record, echoed)) in records.iter().zip(observed.iter()).enumerate() {
+        match echoed {
+            Ok((_, values)) if matches(record, *values, tolerance) => (),
+            Ok((_, (linear, angular))) => {
+                mismatches += 1;
+                println!(
+                    "  mismatch #{}: recorded ({}, {}), observed ({}, {})",
+                    i + 1,
+                    record.linear,
+                    record.angular,
+                    linear,
+                    angular
+                );
+            }
+            Err(e) => {
+                mismatches += 1;
+                println!("  mismatch #{}: {}", i + 1, e);
+            }
+        }
+    }
+    let missing = records.len().saturating_sub(observed.len());
+    if missing > 0 {
+        println!("  {} recorded commands not observed", missing);
+    }
+    let unexpected = observed.len().saturating_sub(records.len());
+    if unexpected > 0 {
+        println!(
+            "  {} unexpected commands observed (another publisher on {}?)",
+            unexpected, cmd_vel
+        );
+    }
+    mismatches == 0 && missing == 0 && unexpected == 0
+}
+
+// Check the echoed commands by their header seq, the recorded ones being published with the
+// seqs from `first`: the gaps, the reordered and the unexpected commands are reported separately
+fn check_by_seq(
+    records: &[Record],
+    observed: &[Echoed],
+    first: u32,
+    tolerance: f64,
+    cmd_vel: &str,
+) -> bool {
+    let mut seen = vec![false; records.len()];
+    let (mut mismatches, mut reordered, mut unexpected) = (0, 0, 0);
+    // the highest index observed so far
+    let mut highest: Option<usize> = None;
+    for echoed in observed.iter() {
+        let (seq, values) = match echoed {
+            Ok((Some(seq), values)) => (*seq, *values),
+            Ok((None, _)) => {
+                unexpected += 1;
+                continue;
+            }
+            Err(e) => {
+                mismatches += 1;
+                println!("  mismatch: {}", e);
+                continue;
+            }
+        };
+        let i = seq.wrapping_sub(first) as usize;
+        // Note: a seq out of the replayed ones, or observed twice, is unexpected
+        if i >= records.len() || seen[i] {
+            unexpected += 1;
+            continue;
+        }
+        seen[i] = true;
+        if highest.is_some_and(|h| i < h) {
+            reordered += 1;
+        }
+        highest = highest.max(Some(i));
+        let record = &records[i];
+        if !matches(record, values, tolerance) {
+            mismatches += 1;
+            println!(
+                "  mismatch seq {}: recorded ({}, {}), observed ({}, {})",
+                seq, record.linear, record.angular, values.0, values.1
+            );
+        }
+    }
+    let gaps: Vec<u32> = seen
+        .iter()
+        .enumerate()
+        .filter(|(_, &seen)| !seen)
+        .map(|(i, _)| first.wrapping_add(i as u32))
+        .collect();
+    if !gaps.is_empty() {
+        println!(
+            "  {} recorded commands not observed, gaps: seq {}",
+            gaps.len(),
+            format_gaps(&gaps)
+        );
+    }
+    if reordered > 0 {
+        println!("  {} commands observed out of order", reordered);
+    }
+    if unexpected > 0 {
+        println!(
+            "  {} unexpected commands observed (another publisher on {}?)",
+            unexpected, cmd_vel
+        );
+    }
+    mismatches == 0 && gaps.is_empty() && reordered == 0 && unexpected == 0
+}
+
+// Replay the recorded commands on `cmd_vel` with their original timing, while a 2nd session
+// (as a remote subscriber) echoes them, then check that every command was observed in order,
+// with the same values within the tolerance (matched by header seq if included, by order otherwise).
+// Returns false on any mismatch.
+pub async fn run(
+    session: &Session,
+    echo_config: Properties,
+    cmd_vel: &str,
+    args: VerifyArgs,
+    stamper: Option<&Stamper>,
+    qos: QosProfile,
+    metrics: &Metrics,
+) -> bool {
+    // Note: a subscriber of the same session would receive the publications locally,
+    // without going through the transport
+    let mut echo_config = echo_config;
+    // The echo session can't listen on the same locators: it connects to them instead
+    if let Some(listeners) = echo_config.remove("listener") {
+        let peers = match echo_config.get("peer") {
+            Some(peers) if !peers.is_empty() => format!("{},{}", peers, listeners),
+            _ => listeners,
+        };
+        echo_config.insert("peer".to_string(), peers);
+    }
+    let echo = match open(echo_config.into()).await {
+        Ok(echo) => echo,
+        Err(e) => {
+            println!("FAIL: failed to open the echo session: {}", e);
+            return false;
+        }
+    };
+    let start = Instant::now();
+    loop {
+        let info = echo.info().await;
+        let connected = [
+            zenoh::net::info::ZN_INFO_PEER_PID_KEY,
+            zenoh::net::info::ZN_INFO_ROUTER_PID_KEY,
+        ]
+        .iter()
+        .any(|key| info.get(key).is_some_and(|pids| !pids.is_empty()));
+        if connected {
+            break;
+        }
+        if start.elapsed() > CONNECT_TIMEOUT {
+            println!("FAIL: the echo session didn't connect to any peer or router");
+            return false;
+        }
+        async_std::task::sleep(Duration::from_millis(100)).await;
+    }
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+        period: None,
+    };
+    let mut subscriber = match echo.declare_subscriber(&cmd_vel.into(), &sub_info).await {
+        Ok(subscriber) => subscriber,
+        Err(e) => {
+            println!("FAIL: failed to subscribe to {}: {}", cmd_vel, e);
+            return false;
+        }
+    };
+    // Let the subscription reach the publisher's session
+    async_std::task::sleep(Duration::from_millis(500)).await;
+
+    println!(
+        "Replaying {} commands on {}...",
+        args.records.len(),
+        cmd_vel
+    );
+    // Note: the seqs of the replayed commands start with the stamper's next one
+    let first_seq = stamper.and_then(Stamper::next_seq);
+    let mut observed: Vec<Echoed> = vec![];
+    {
+        let collect = subscriber.receiver().for_each(|sample| {
+            observed.push(decode(sample.payload, stamper));
+            future::ready(())
+        });
+        let replay = async {
+            let first = args.records.first().map(|r| r.time).unwrap_or(0.0);
+            let start = Instant::now();
+            for record in args.records.iter() {
+                let at = start + Duration::from_secs_f64((record.time - first).max(0.0));
+                async_std::task::sleep(at.saturating_duration_since(Instant::now())).await;
+                let key = ResKey::from(cmd_vel);
+                if let Err(e) = crate::pub_twist(
+                    session,
+                    &key,
+                    record.linear,
+                    record.angular,
+                    stamper,
+                    qos,
+                    metrics,
+                )
+                .await
+                {
+                    println!(
+                        "Failed to publish ({}, {}): {}",
+                        record.linear, record.angular, e
+                    );
+                }
+            }
+            async_std::task::sleep(SETTLE_DELAY).await;
+        };
+        futures::pin_mut!(collect, replay);
+        future::select(collect, replay).await;
+    }
+
+    println!(
+        "Replay verification: {} commands recorded, {} observed",
+        args.records.len(),
+        observed.len()
+    );
+    let passed = match first_seq {
+        Some(first) => check_by_seq(&args.records, &observed, first, args.tolerance, cmd_vel),
+        None => check_by_order(&args.records, &observed, args.tolerance, cmd_vel),
+    };
+    println!("{}", if passed { "PASS" } else { "FAIL" });
+    passed
+}